@@ -0,0 +1,53 @@
+// cargo bench --bench loess
+// cargo +nightly bench --bench loess --features simd
+
+use std::time::{Duration, Instant};
+
+fn generate_series(n: usize) -> Vec<f64> {
+    let mut state = 42u64;
+    (0..n)
+        .map(|i| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+            let noise = (state >> 11) as f64 / (1u64 << 53) as f64 - 0.5;
+            let daily = (2.0 * std::f64::consts::PI * (i % 24) as f64 / 24.0).sin();
+            10.0 + 0.00001 * i as f64 + 3.0 * daily + noise
+        })
+        .collect()
+}
+
+fn time<F: FnMut()>(mut f: F) -> Duration {
+    let start = Instant::now();
+    f();
+    start.elapsed()
+}
+
+fn main() {
+    let series = generate_series(1_000_000);
+
+    for trend_length in [37, 101, 1001] {
+        let mut params = stlrs::Stl::params();
+        params
+            .trend_length(trend_length)
+            .seasonal_jump(1)
+            .trend_jump(1)
+            .low_pass_jump(1);
+
+        let mut expected = None;
+        let standard = time(|| expected = Some(params.fit(&series, 24).unwrap()));
+        let mut result = None;
+        let fast = time(|| result = Some(params.fast_loess(true).fit(&series, 24).unwrap()));
+        params.fast_loess(false);
+
+        let max_diff = expected
+            .unwrap()
+            .trend()
+            .iter()
+            .zip(result.unwrap().trend())
+            .map(|(a, b)| (a - b).abs())
+            .fold(0.0, f64::max);
+        println!(
+            "trend_length {:>4}: standard {:>8.2?}, fast {:>8.2?}, max trend diff {:.1e}",
+            trend_length, standard, fast, max_diff
+        );
+    }
+}
@@ -0,0 +1,221 @@
+#ifndef STLRS_H
+#define STLRS_H
+
+/* Generated with cbindgen. Do not edit manually. */
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+// Success.
+#define STLRS_OK 0
+
+// Invalid parameter.
+#define STLRS_ERROR_PARAMETER 1
+
+// Invalid series.
+#define STLRS_ERROR_SERIES 2
+
+// Null pointer passed for a required argument.
+#define STLRS_ERROR_NULL_POINTER 3
+
+// An opaque set of MSTL parameters.
+typedef struct stlrs_mstl_params stlrs_mstl_params;
+
+// An opaque set of STL parameters.
+typedef struct stlrs_stl_params stlrs_stl_params;
+
+// Returns the message for the last error on the current thread, or null if there is none.
+//
+// The pointer is valid until the next call that fails on the same thread.
+const char *stlrs_last_error_message(void);
+
+// Creates a new set of STL parameters.
+struct stlrs_stl_params *stlrs_stl_params_new(void);
+
+// Frees a set of STL parameters.
+//
+// # Safety
+//
+// `params` must be null or returned by `stlrs_stl_params_new` and not already freed.
+void stlrs_stl_params_free(struct stlrs_stl_params *params);
+
+// Sets the length of the seasonal smoother.
+//
+// # Safety
+//
+// `params` must be a valid pointer returned by `stlrs_stl_params_new`.
+int stlrs_stl_params_seasonal_length(struct stlrs_stl_params *params, size_t length);
+
+// Sets the length of the trend smoother.
+//
+// # Safety
+//
+// `params` must be a valid pointer returned by `stlrs_stl_params_new`.
+int stlrs_stl_params_trend_length(struct stlrs_stl_params *params, size_t length);
+
+// Sets the length of the low-pass filter.
+//
+// # Safety
+//
+// `params` must be a valid pointer returned by `stlrs_stl_params_new`.
+int stlrs_stl_params_low_pass_length(struct stlrs_stl_params *params, size_t length);
+
+// Sets the degree of locally-fitted polynomial in seasonal smoothing.
+//
+// # Safety
+//
+// `params` must be a valid pointer returned by `stlrs_stl_params_new`.
+int stlrs_stl_params_seasonal_degree(struct stlrs_stl_params *params, int32_t degree);
+
+// Sets the degree of locally-fitted polynomial in trend smoothing.
+//
+// # Safety
+//
+// `params` must be a valid pointer returned by `stlrs_stl_params_new`.
+int stlrs_stl_params_trend_degree(struct stlrs_stl_params *params, int32_t degree);
+
+// Sets the degree of locally-fitted polynomial in low-pass smoothing.
+//
+// # Safety
+//
+// `params` must be a valid pointer returned by `stlrs_stl_params_new`.
+int stlrs_stl_params_low_pass_degree(struct stlrs_stl_params *params, int32_t degree);
+
+// Sets the skipping value for seasonal smoothing.
+//
+// # Safety
+//
+// `params` must be a valid pointer returned by `stlrs_stl_params_new`.
+int stlrs_stl_params_seasonal_jump(struct stlrs_stl_params *params, size_t jump);
+
+// Sets the skipping value for trend smoothing.
+//
+// # Safety
+//
+// `params` must be a valid pointer returned by `stlrs_stl_params_new`.
+int stlrs_stl_params_trend_jump(struct stlrs_stl_params *params, size_t jump);
+
+// Sets the skipping value for low-pass smoothing.
+//
+// # Safety
+//
+// `params` must be a valid pointer returned by `stlrs_stl_params_new`.
+int stlrs_stl_params_low_pass_jump(struct stlrs_stl_params *params, size_t jump);
+
+// Sets the number of loops for updating the seasonal and trend components.
+//
+// # Safety
+//
+// `params` must be a valid pointer returned by `stlrs_stl_params_new`.
+int stlrs_stl_params_inner_loops(struct stlrs_stl_params *params, size_t loops);
+
+// Sets the number of iterations of robust fitting.
+//
+// # Safety
+//
+// `params` must be a valid pointer returned by `stlrs_stl_params_new`.
+int stlrs_stl_params_outer_loops(struct stlrs_stl_params *params, size_t loops);
+
+// Sets whether robustness iterations are to be used.
+//
+// # Safety
+//
+// `params` must be a valid pointer returned by `stlrs_stl_params_new`.
+int stlrs_stl_params_robust(struct stlrs_stl_params *params, bool robust);
+
+// Sets whether to check the series for non-finite values (enabled by default).
+//
+// # Safety
+//
+// `params` must be a valid pointer returned by `stlrs_stl_params_new`.
+int stlrs_stl_params_check_finite(struct stlrs_stl_params *params, bool check_finite);
+
+// Decomposes a time series.
+//
+// Each output buffer must have room for `n` values and may be null to skip that component.
+//
+// # Safety
+//
+// `params` must be a valid pointer returned by `stlrs_stl_params_new`,
+// `series` must point to `n` values, and non-null outputs must point to `n` writable values.
+int stlrs_stl_fit(const struct stlrs_stl_params *params,
+                  const double *series,
+                  size_t n,
+                  size_t period,
+                  double *seasonal,
+                  double *trend,
+                  double *remainder,
+                  double *weights);
+
+// Creates a new set of MSTL parameters.
+struct stlrs_mstl_params *stlrs_mstl_params_new(void);
+
+// Frees a set of MSTL parameters.
+//
+// # Safety
+//
+// `params` must be null or returned by `stlrs_mstl_params_new` and not already freed.
+void stlrs_mstl_params_free(struct stlrs_mstl_params *params);
+
+// Sets the number of iterations.
+//
+// # Safety
+//
+// `params` must be a valid pointer returned by `stlrs_mstl_params_new`.
+int stlrs_mstl_params_iterations(struct stlrs_mstl_params *params, size_t iterations);
+
+// Sets lambda for Box-Cox transformation.
+//
+// # Safety
+//
+// `params` must be a valid pointer returned by `stlrs_mstl_params_new`.
+int stlrs_mstl_params_lambda(struct stlrs_mstl_params *params, float lambda);
+
+// Sets whether to check the series for non-finite values (enabled by default).
+//
+// # Safety
+//
+// `params` must be a valid pointer returned by `stlrs_mstl_params_new`.
+int stlrs_mstl_params_check_finite(struct stlrs_mstl_params *params, bool check_finite);
+
+// Sets the lengths of the seasonal smoothers.
+//
+// # Safety
+//
+// `params` must be a valid pointer returned by `stlrs_mstl_params_new`
+// and `lengths` must point to `len` values.
+int stlrs_mstl_params_seasonal_lengths(struct stlrs_mstl_params *params,
+                                       const size_t *lengths,
+                                       size_t len);
+
+// Sets the STL parameters, which are copied.
+//
+// # Safety
+//
+// `params` must be a valid pointer returned by `stlrs_mstl_params_new`
+// and `stl_params` must be a valid pointer returned by `stlrs_stl_params_new`.
+int stlrs_mstl_params_stl_params(struct stlrs_mstl_params *params,
+                                 const struct stlrs_stl_params *stl_params);
+
+// Decomposes a time series with multiple seasonal periods.
+//
+// `seasonal` must have room for `periods_len * n` values and is filled with one
+// component after another in the order of `periods`. `trend` and `remainder` must
+// have room for `n` values. Any output may be null to skip that component.
+//
+// # Safety
+//
+// `params` must be a valid pointer returned by `stlrs_mstl_params_new`,
+// `series` must point to `n` values, `periods` must point to `periods_len` values,
+// and non-null outputs must point to enough writable values.
+int stlrs_mstl_fit(const struct stlrs_mstl_params *params,
+                   const double *series,
+                   size_t n,
+                   const size_t *periods,
+                   size_t periods_len,
+                   double *seasonal,
+                   double *trend,
+                   double *remainder);
+
+#endif  /* STLRS_H */
@@ -0,0 +1,243 @@
+use alloc::vec::Vec;
+use ndarray::{Array2, ArrayView1, ArrayView2, Axis};
+
+use super::{Error, Float, Mstl, MstlParams, Stl, StlParams};
+
+/// A STL result for a 2-D array.
+#[derive(Clone, Debug)]
+pub struct StlArrayResult<T: Float = f32> {
+    seasonal: Array2<T>,
+    trend: Array2<T>,
+    remainder: Array2<T>,
+    weights: Array2<T>,
+}
+
+/// A MSTL result for a 2-D array.
+#[derive(Clone, Debug)]
+pub struct MstlArrayResult<T: Float = f32> {
+    seasonal: Vec<Array2<T>>,
+    trend: Array2<T>,
+    remainder: Array2<T>,
+}
+
+impl<T: Float> StlArrayResult<T> {
+    /// Returns the seasonal component.
+    pub fn seasonal(&self) -> &Array2<T> {
+        &self.seasonal
+    }
+
+    /// Returns the trend component.
+    pub fn trend(&self) -> &Array2<T> {
+        &self.trend
+    }
+
+    /// Returns the remainder.
+    pub fn remainder(&self) -> &Array2<T> {
+        &self.remainder
+    }
+
+    /// Returns the weights.
+    pub fn weights(&self) -> &Array2<T> {
+        &self.weights
+    }
+
+    /// Consumes the result, returning the seasonal component, trend component, remainder, and weights.
+    pub fn into_parts(self) -> (Array2<T>, Array2<T>, Array2<T>, Array2<T>) {
+        (self.seasonal, self.trend, self.remainder, self.weights)
+    }
+}
+
+impl<T: Float> MstlArrayResult<T> {
+    /// Returns the seasonal components.
+    pub fn seasonal(&self) -> &[Array2<T>] {
+        &self.seasonal[..]
+    }
+
+    /// Returns the trend component.
+    pub fn trend(&self) -> &Array2<T> {
+        &self.trend
+    }
+
+    /// Returns the remainder.
+    pub fn remainder(&self) -> &Array2<T> {
+        &self.remainder
+    }
+
+    /// Consumes the result, returning the seasonal components, trend component, and remainder.
+    pub fn into_parts(self) -> (Vec<Array2<T>>, Array2<T>, Array2<T>) {
+        (self.seasonal, self.trend, self.remainder)
+    }
+}
+
+impl Stl {
+    /// Decomposes each lane of a 2-D array along an axis.
+    pub fn fit_axis<T: Float>(
+        data: ArrayView2<T>,
+        axis: Axis,
+        period: usize,
+    ) -> Result<StlArrayResult<T>, Error> {
+        StlParams::new().fit_axis(data, axis, period)
+    }
+}
+
+impl Mstl {
+    /// Decomposes each lane of a 2-D array along an axis.
+    pub fn fit_axis<T: Float>(
+        data: ArrayView2<T>,
+        axis: Axis,
+        periods: &[usize],
+    ) -> Result<MstlArrayResult<T>, Error> {
+        MstlParams::new().fit_axis(data, axis, periods)
+    }
+}
+
+impl StlParams {
+    /// Decomposes each lane of a 2-D array along an axis.
+    pub fn fit_axis<T: Float>(
+        &self,
+        data: ArrayView2<T>,
+        axis: Axis,
+        period: usize,
+    ) -> Result<StlArrayResult<T>, Error> {
+        let mut seasonal = Array2::from_elem(data.raw_dim(), T::zero());
+        let mut trend = seasonal.clone();
+        let mut remainder = seasonal.clone();
+        let mut weights = seasonal.clone();
+
+        for (i, lane) in data.lanes(axis).into_iter().enumerate() {
+            let result = self.fit(&lane.to_vec(), period)?;
+            let j = Axis(1 - axis.index());
+            seasonal
+                .index_axis_mut(j, i)
+                .assign(&ArrayView1::from(&result.seasonal[..]));
+            trend
+                .index_axis_mut(j, i)
+                .assign(&ArrayView1::from(&result.trend[..]));
+            remainder
+                .index_axis_mut(j, i)
+                .assign(&ArrayView1::from(&result.remainder[..]));
+            weights
+                .index_axis_mut(j, i)
+                .assign(&ArrayView1::from(&result.weights[..]));
+        }
+
+        Ok(StlArrayResult {
+            seasonal,
+            trend,
+            remainder,
+            weights,
+        })
+    }
+}
+
+impl MstlParams {
+    /// Decomposes each lane of a 2-D array along an axis.
+    pub fn fit_axis<T: Float>(
+        &self,
+        data: ArrayView2<T>,
+        axis: Axis,
+        periods: &[usize],
+    ) -> Result<MstlArrayResult<T>, Error> {
+        let mut seasonal = Vec::with_capacity(periods.len());
+        for _ in 0..periods.len() {
+            seasonal.push(Array2::from_elem(data.raw_dim(), T::zero()));
+        }
+        let mut trend = Array2::from_elem(data.raw_dim(), T::zero());
+        let mut remainder = trend.clone();
+
+        for (i, lane) in data.lanes(axis).into_iter().enumerate() {
+            let result = self.fit(&lane.to_vec(), periods)?;
+            let j = Axis(1 - axis.index());
+            for (s, rs) in seasonal.iter_mut().zip(&result.seasonal) {
+                s.index_axis_mut(j, i).assign(&ArrayView1::from(&rs[..]));
+            }
+            trend
+                .index_axis_mut(j, i)
+                .assign(&ArrayView1::from(&result.trend[..]));
+            remainder
+                .index_axis_mut(j, i)
+                .assign(&ArrayView1::from(&result.remainder[..]));
+        }
+
+        Ok(MstlArrayResult {
+            seasonal,
+            trend,
+            remainder,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stl::test_helpers::*;
+    use crate::{Error, Mstl, Stl};
+    use alloc::vec::Vec;
+    use ndarray::{Array2, Axis};
+
+    fn generate_data() -> Array2<f32> {
+        let series = [
+            5.0, 9.0, 2.0, 9.0, 0.0, 6.0, 3.0, 8.0, 5.0, 8.0, 7.0, 8.0, 8.0, 0.0, 2.0, 5.0, 0.0,
+            5.0, 6.0, 7.0, 3.0, 6.0, 1.0, 4.0, 4.0, 4.0, 3.0, 7.0, 5.0, 8.0,
+        ];
+        Array2::from_shape_fn((30, 2), |(i, j)| series[i] * (j + 1) as f32)
+    }
+
+    #[test]
+    fn test_stl() {
+        let data = generate_data();
+        let result = Stl::fit_axis(data.view(), Axis(0), 7).unwrap();
+        assert_eq!(data.shape(), result.seasonal().shape());
+        for (j, column) in data.columns().into_iter().enumerate() {
+            let expected = Stl::fit(&column.to_vec(), 7).unwrap();
+            assert_elements_in_delta(expected.seasonal(), &result.seasonal().column(j).to_vec());
+            assert_elements_in_delta(expected.trend(), &result.trend().column(j).to_vec());
+            assert_elements_in_delta(expected.remainder(), &result.remainder().column(j).to_vec());
+            assert_elements_in_delta(expected.weights(), &result.weights().column(j).to_vec());
+        }
+    }
+
+    #[test]
+    fn test_stl_axis_one() {
+        let data = generate_data();
+        let result = Stl::params()
+            .robust(true)
+            .fit_axis(data.t(), Axis(1), 7)
+            .unwrap();
+        assert_eq!(&[2, 30], result.trend().shape());
+        let expected = Stl::params()
+            .robust(true)
+            .fit_axis(data.view(), Axis(0), 7)
+            .unwrap();
+        assert_elements_in_delta(
+            &expected.trend().t().iter().copied().collect::<Vec<f32>>(),
+            &result.trend().iter().copied().collect::<Vec<f32>>(),
+        );
+    }
+
+    #[test]
+    fn test_mstl() {
+        let data = generate_data();
+        let result = Mstl::fit_axis(data.view(), Axis(0), &[6, 10]).unwrap();
+        assert_eq!(2, result.seasonal().len());
+        let expected = Mstl::fit(&data.column(1).to_vec(), &[6, 10]).unwrap();
+        assert_elements_in_delta(
+            &expected.seasonal()[1],
+            &result.seasonal()[1].column(1).to_vec(),
+        );
+        assert_elements_in_delta(expected.trend(), &result.trend().column(1).to_vec());
+        assert_elements_in_delta(expected.remainder(), &result.remainder().column(1).to_vec());
+    }
+
+    #[test]
+    fn test_too_few_periods() {
+        let data = generate_data();
+        let result = Stl::fit_axis(data.view(), Axis(1), 7);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::SeriesTooShort {
+                len: 2,
+                min_len: 14
+            }
+        );
+    }
+}
@@ -0,0 +1,514 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Float32Type, Float64Type, Int32Type, Int64Type, UInt32Type, UInt64Type};
+use arrow_array::{
+    Array, ArrayRef, ArrowPrimitiveType, PrimitiveArray, RecordBatch, StructArray, UInt64Array,
+};
+use arrow_schema::{DataType, Field, Schema};
+
+use super::missing;
+use super::{Error, Float, MstlParams, StlParams};
+
+/// A STL result for an Arrow array.
+#[derive(Clone, Debug)]
+pub struct StlArrowResult<A: ArrowPrimitiveType> {
+    seasonal: PrimitiveArray<A>,
+    trend: PrimitiveArray<A>,
+    remainder: PrimitiveArray<A>,
+    weights: PrimitiveArray<A>,
+}
+
+/// A MSTL result for an Arrow array.
+#[derive(Clone, Debug)]
+pub struct MstlArrowResult<A: ArrowPrimitiveType> {
+    periods: Vec<usize>,
+    seasonal: Vec<PrimitiveArray<A>>,
+    trend: PrimitiveArray<A>,
+    remainder: PrimitiveArray<A>,
+}
+
+impl<A: ArrowPrimitiveType> StlArrowResult<A> {
+    /// Returns the seasonal component.
+    pub fn seasonal(&self) -> &PrimitiveArray<A> {
+        &self.seasonal
+    }
+
+    /// Returns the trend component.
+    pub fn trend(&self) -> &PrimitiveArray<A> {
+        &self.trend
+    }
+
+    /// Returns the remainder, which is null where the series is null.
+    pub fn remainder(&self) -> &PrimitiveArray<A> {
+        &self.remainder
+    }
+
+    /// Returns the weights.
+    pub fn weights(&self) -> &PrimitiveArray<A> {
+        &self.weights
+    }
+
+    /// Returns the components as a struct array with `seasonal`, `trend`, `remainder`, and `weights` fields.
+    pub fn to_struct_array(&self) -> StructArray {
+        struct_array(vec![
+            ("seasonal".to_string(), self.seasonal.clone()),
+            ("trend".to_string(), self.trend.clone()),
+            ("remainder".to_string(), self.remainder.clone()),
+            ("weights".to_string(), self.weights.clone()),
+        ])
+    }
+}
+
+impl<A: ArrowPrimitiveType> MstlArrowResult<A> {
+    /// Returns the seasonal components.
+    pub fn seasonal(&self) -> &[PrimitiveArray<A>] {
+        &self.seasonal[..]
+    }
+
+    /// Returns the trend component.
+    pub fn trend(&self) -> &PrimitiveArray<A> {
+        &self.trend
+    }
+
+    /// Returns the remainder, which is null where the series is null.
+    pub fn remainder(&self) -> &PrimitiveArray<A> {
+        &self.remainder
+    }
+
+    /// Returns the components as a struct array with `seasonal_{period}`, `trend`, and `remainder` fields.
+    pub fn to_struct_array(&self) -> StructArray {
+        let mut columns = self
+            .periods
+            .iter()
+            .zip(&self.seasonal)
+            .map(|(np, s)| (format!("seasonal_{}", np), s.clone()))
+            .collect::<Vec<_>>();
+        columns.push(("trend".to_string(), self.trend.clone()));
+        columns.push(("remainder".to_string(), self.remainder.clone()));
+        struct_array(columns)
+    }
+}
+
+impl StlParams {
+    /// Decomposes an Arrow array, treating nulls as missing values.
+    pub fn fit_arrow<A>(
+        &self,
+        array: &PrimitiveArray<A>,
+        period: usize,
+    ) -> Result<StlArrowResult<A>, Error>
+    where
+        A: ArrowPrimitiveType,
+        A::Native: Float,
+    {
+        let series = interpolate(array)?;
+        let result = self.fit(&series, period)?;
+        let (seasonal, trend, remainder, weights) = result.into_parts();
+        Ok(StlArrowResult {
+            seasonal: PrimitiveArray::from_iter_values(seasonal),
+            trend: PrimitiveArray::from_iter_values(trend),
+            remainder: PrimitiveArray::new(remainder.into(), array.nulls().cloned()),
+            weights: PrimitiveArray::from_iter_values(weights),
+        })
+    }
+
+    /// Decomposes each series in a long-format record batch.
+    ///
+    /// Rows for each series id must be in time order. Returns a record batch
+    /// with the id column followed by the components, in the original row order.
+    pub fn fit_grouped(
+        &self,
+        batch: &RecordBatch,
+        id_column: &str,
+        value_column: &str,
+        period: usize,
+    ) -> Result<RecordBatch, Error> {
+        fit_grouped(batch, id_column, value_column, |values| {
+            Ok(match values.data_type() {
+                DataType::Float32 => self
+                    .fit_arrow(values.as_primitive::<Float32Type>(), period)?
+                    .to_struct_array(),
+                DataType::Float64 => self
+                    .fit_arrow(values.as_primitive::<Float64Type>(), period)?
+                    .to_struct_array(),
+                _ => unreachable!(),
+            })
+        })
+    }
+}
+
+impl MstlParams {
+    /// Decomposes an Arrow array, treating nulls as missing values.
+    pub fn fit_arrow<A>(
+        &self,
+        array: &PrimitiveArray<A>,
+        periods: &[usize],
+    ) -> Result<MstlArrowResult<A>, Error>
+    where
+        A: ArrowPrimitiveType,
+        A::Native: Float,
+    {
+        let series = interpolate(array)?;
+        let result = self.fit(&series, periods)?;
+        let (seasonal, trend, remainder) = result.into_parts();
+        Ok(MstlArrowResult {
+            periods: periods.to_vec(),
+            seasonal: seasonal
+                .into_iter()
+                .map(PrimitiveArray::from_iter_values)
+                .collect(),
+            trend: PrimitiveArray::from_iter_values(trend),
+            remainder: PrimitiveArray::new(remainder.into(), array.nulls().cloned()),
+        })
+    }
+
+    /// Decomposes each series in a long-format record batch.
+    ///
+    /// Rows for each series id must be in time order. Returns a record batch
+    /// with the id column followed by the components, in the original row order.
+    pub fn fit_grouped(
+        &self,
+        batch: &RecordBatch,
+        id_column: &str,
+        value_column: &str,
+        periods: &[usize],
+    ) -> Result<RecordBatch, Error> {
+        fit_grouped(batch, id_column, value_column, |values| {
+            Ok(match values.data_type() {
+                DataType::Float32 => self
+                    .fit_arrow(values.as_primitive::<Float32Type>(), periods)?
+                    .to_struct_array(),
+                DataType::Float64 => self
+                    .fit_arrow(values.as_primitive::<Float64Type>(), periods)?
+                    .to_struct_array(),
+                _ => unreachable!(),
+            })
+        })
+    }
+}
+
+fn interpolate<A>(array: &PrimitiveArray<A>) -> Result<Vec<A::Native>, Error>
+where
+    A: ArrowPrimitiveType,
+    A::Native: Float,
+{
+    let mut series = array.values().to_vec();
+    if array.null_count() > 0 {
+        let valid = (0..array.len())
+            .filter(|&i| array.is_valid(i))
+            .collect::<Vec<usize>>();
+        missing::interpolate(&mut series, &valid)?;
+    }
+    Ok(series)
+}
+
+fn struct_array<A: ArrowPrimitiveType>(columns: Vec<(String, PrimitiveArray<A>)>) -> StructArray {
+    let (fields, arrays): (Vec<Field>, Vec<ArrayRef>) = columns
+        .into_iter()
+        .map(|(name, array)| {
+            let field = Field::new(name, A::DATA_TYPE, array.null_count() > 0);
+            (field, Arc::new(array) as ArrayRef)
+        })
+        .unzip();
+    StructArray::new(fields.into(), arrays, None)
+}
+
+#[derive(Eq, Hash, PartialEq)]
+enum Key {
+    Int(i128),
+    Str(String),
+}
+
+fn keys(ids: &dyn Array) -> Result<Vec<Option<Key>>, Error> {
+    macro_rules! int_keys {
+        ($t:ty) => {
+            ids.as_primitive::<$t>()
+                .iter()
+                .map(|v| v.map(|v| Key::Int(v as i128)))
+                .collect()
+        };
+    }
+
+    Ok(match ids.data_type() {
+        DataType::Int32 => int_keys!(Int32Type),
+        DataType::Int64 => int_keys!(Int64Type),
+        DataType::UInt32 => int_keys!(UInt32Type),
+        DataType::UInt64 => int_keys!(UInt64Type),
+        DataType::Utf8 => ids
+            .as_string::<i32>()
+            .iter()
+            .map(|v| v.map(|v| Key::Str(v.to_string())))
+            .collect(),
+        DataType::LargeUtf8 => ids
+            .as_string::<i64>()
+            .iter()
+            .map(|v| v.map(|v| Key::Str(v.to_string())))
+            .collect(),
+        _ => {
+            return Err(Error::InvalidColumn {
+                name: "id_column",
+                reason: "must be an integer or string column",
+            })
+        }
+    })
+}
+
+fn fit_grouped<F>(
+    batch: &RecordBatch,
+    id_column: &str,
+    value_column: &str,
+    fit: F,
+) -> Result<RecordBatch, Error>
+where
+    F: Fn(&dyn Array) -> Result<StructArray, Error>,
+{
+    let ids = batch
+        .column_by_name(id_column)
+        .ok_or(Error::ColumnNotFound { name: "id_column" })?;
+    let values = batch
+        .column_by_name(value_column)
+        .ok_or(Error::ColumnNotFound {
+            name: "value_column",
+        })?;
+    if !matches!(values.data_type(), DataType::Float32 | DataType::Float64) {
+        return Err(Error::InvalidColumn {
+            name: "value_column",
+            reason: "must be a Float32 or Float64 column",
+        });
+    }
+
+    // group row indices by id, in order of first appearance
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut lookup = HashMap::new();
+    for (i, key) in keys(ids.as_ref())?.into_iter().enumerate() {
+        let key = key.ok_or(Error::InvalidColumn {
+            name: "id_column",
+            reason: "must not contain nulls",
+        })?;
+        let g = *lookup.entry(key).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[g].push(i);
+    }
+
+    let mut fields = None;
+    let mut parts = Vec::with_capacity(groups.len());
+    for rows in &groups {
+        let indices = UInt64Array::from_iter_values(rows.iter().map(|&i| i as u64));
+        let group_values = take(values.as_ref(), &indices);
+        let components = fit(group_values.as_ref())?;
+        fields.get_or_insert_with(|| components.fields().clone());
+        parts.push(components);
+    }
+
+    // scatter group results back to the original row order
+    let n = batch.num_rows();
+    let mut order = vec![(0, 0); n];
+    for (g, rows) in groups.iter().enumerate() {
+        for (j, &i) in rows.iter().enumerate() {
+            order[i] = (g, j);
+        }
+    }
+
+    let mut schema_fields = vec![batch.schema().field_with_name(id_column).unwrap().clone()];
+    let mut columns = vec![ids.clone()];
+    if let Some(fields) = fields {
+        for (c, field) in fields.iter().enumerate() {
+            let column = match values.data_type() {
+                DataType::Float32 => gather::<Float32Type>(&parts, c, &order),
+                _ => gather::<Float64Type>(&parts, c, &order),
+            };
+            schema_fields.push(
+                field
+                    .as_ref()
+                    .clone()
+                    .with_nullable(column.null_count() > 0),
+            );
+            columns.push(column);
+        }
+    }
+
+    Ok(RecordBatch::try_new(Arc::new(Schema::new(schema_fields)), columns).unwrap())
+}
+
+fn take(values: &dyn Array, indices: &UInt64Array) -> ArrayRef {
+    fn take_primitive<A: ArrowPrimitiveType>(
+        values: &dyn Array,
+        indices: &UInt64Array,
+    ) -> ArrayRef {
+        let values = values.as_primitive::<A>();
+        let array = indices
+            .values()
+            .iter()
+            .map(|&i| {
+                let i = i as usize;
+                values.is_valid(i).then(|| values.value(i))
+            })
+            .collect::<PrimitiveArray<A>>();
+        Arc::new(array)
+    }
+
+    match values.data_type() {
+        DataType::Float32 => take_primitive::<Float32Type>(values, indices),
+        _ => take_primitive::<Float64Type>(values, indices),
+    }
+}
+
+fn gather<A: ArrowPrimitiveType>(
+    parts: &[StructArray],
+    column: usize,
+    order: &[(usize, usize)],
+) -> ArrayRef {
+    let columns = parts
+        .iter()
+        .map(|p| p.column(column).as_primitive::<A>())
+        .collect::<Vec<_>>();
+    let values = order
+        .iter()
+        .map(|&(g, j)| {
+            let c = columns[g];
+            c.is_valid(j).then(|| c.value(j))
+        })
+        .collect::<PrimitiveArray<A>>();
+    let nulls = values.nulls().filter(|n| n.null_count() > 0).cloned();
+    Arc::new(PrimitiveArray::<A>::new(values.values().clone(), nulls))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stl::test_helpers::*;
+    use crate::{Error, Mstl, Stl};
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::Float64Type;
+    use arrow_array::{Array, Float32Array, Float64Array, RecordBatch, StringArray};
+    use std::sync::Arc;
+
+    fn generate_series() -> Vec<f32> {
+        vec![
+            5.0, 9.0, 2.0, 9.0, 0.0, 6.0, 3.0, 8.0, 5.0, 8.0, 7.0, 8.0, 8.0, 0.0, 2.0, 5.0, 0.0,
+            5.0, 6.0, 7.0, 3.0, 6.0, 1.0, 4.0, 4.0, 4.0, 3.0, 7.0, 5.0, 8.0,
+        ]
+    }
+
+    #[test]
+    fn test_stl() {
+        let array = Float32Array::from(generate_series());
+        let result = Stl::params().fit_arrow(&array, 7).unwrap();
+        assert_elements_in_delta(
+            &[0.36926576, 0.75655484, -1.3324139, 1.9553658, -0.6044802],
+            &result.seasonal().values()[..5],
+        );
+        assert_elements_in_delta(
+            &[4.804099, 4.9097075, 5.015316, 5.16045, 5.305584],
+            &result.trend().values()[..5],
+        );
+        assert_eq!(0, result.remainder().null_count());
+
+        let parts = result.to_struct_array();
+        assert_eq!(4, parts.num_columns());
+        assert_eq!("weights", parts.fields()[3].name());
+    }
+
+    #[test]
+    fn test_stl_nulls() {
+        let mut series = generate_series()
+            .into_iter()
+            .map(|v| Some(v as f64))
+            .collect::<Vec<Option<f64>>>();
+        series[0] = None;
+        series[3] = None;
+        let array = Float64Array::from(series);
+        let result = Stl::params().fit_arrow(&array, 7).unwrap();
+        assert_eq!(0, result.seasonal().null_count());
+        assert_eq!(0, result.trend().null_count());
+        assert!(result.remainder().is_null(0));
+        assert!(result.remainder().is_null(3));
+        assert_eq!(2, result.remainder().null_count());
+
+        // nulls are interpolated before fitting
+        let mut filled = generate_series()
+            .into_iter()
+            .map(|v| v as f64)
+            .collect::<Vec<f64>>();
+        filled[0] = 9.0;
+        filled[3] = 1.0;
+        let expected = Stl::fit(&filled, 7).unwrap();
+        assert_elements_in_delta(expected.trend(), result.trend().values());
+    }
+
+    #[test]
+    fn test_all_nulls() {
+        let array = Float64Array::from(vec![None; 30]);
+        let result = Stl::params().fit_arrow(&array, 7);
+        assert_eq!(result.unwrap_err(), Error::NoValidValues);
+    }
+
+    #[test]
+    fn test_mstl() {
+        let array = Float32Array::from(generate_series());
+        let result = Mstl::params().fit_arrow(&array, &[6, 10]).unwrap();
+        assert_eq!(2, result.seasonal().len());
+        assert_elements_in_delta(
+            &[1.4130436, 1.6048906, 0.050958008, -1.8706754, -1.7704514],
+            &result.seasonal()[1].values()[..5],
+        );
+        let parts = result.to_struct_array();
+        let names = parts
+            .fields()
+            .iter()
+            .map(|f| f.name().as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(
+            vec!["seasonal_6", "seasonal_10", "trend", "remainder"],
+            names
+        );
+    }
+
+    #[test]
+    fn test_fit_grouped() {
+        // interleave two series
+        let series = generate_series();
+        let mut ids = Vec::new();
+        let mut values = Vec::new();
+        for v in &series {
+            ids.push("a");
+            values.push(*v as f64);
+            ids.push("b");
+            values.push(*v as f64 * 2.0);
+        }
+        let batch = RecordBatch::try_from_iter(vec![
+            ("id", Arc::new(StringArray::from(ids)) as _),
+            ("value", Arc::new(Float64Array::from(values)) as _),
+        ])
+        .unwrap();
+
+        let result = Stl::params().fit_grouped(&batch, "id", "value", 7).unwrap();
+        assert_eq!(60, result.num_rows());
+        assert_eq!("id", result.schema().field(0).name());
+
+        let trend = result
+            .column_by_name("trend")
+            .unwrap()
+            .as_primitive::<Float64Type>();
+        let a = (0..5).map(|i| trend.value(2 * i)).collect::<Vec<f64>>();
+        let b = (0..5).map(|i| trend.value(2 * i + 1)).collect::<Vec<f64>>();
+        assert_elements_in_delta(&[4.804099, 4.9097075, 5.015316, 5.16045, 5.305584], &a);
+        assert_elements_in_delta(&[9.608198, 9.819415, 10.030632, 10.3209, 10.611168], &b);
+    }
+
+    #[test]
+    fn test_fit_grouped_missing_column() {
+        let batch = RecordBatch::try_from_iter(vec![(
+            "value",
+            Arc::new(Float64Array::from(vec![1.0; 30])) as _,
+        )])
+        .unwrap();
+        let result = Mstl::params().fit_grouped(&batch, "id", "value", &[7]);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::ColumnNotFound { name: "id_column" }
+        );
+    }
+}
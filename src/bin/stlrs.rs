@@ -0,0 +1,568 @@
+use std::env;
+use std::error::Error;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::process;
+
+use stlrs::{MstlParams, StlParams};
+
+const USAGE: &str = "Usage: stlrs [options] [file]
+
+Decomposes a time series read from a CSV file or newline-delimited numbers.
+Reads from stdin if no file is given.
+
+Input:
+    --period <n>                  period of the seasonal component (repeat or separate with commas for MSTL)
+    --column <name|index>         column to decompose (default: first column)
+    --delimiter <char>            field delimiter (default: ,)
+    --no-header                   first row contains data
+
+Output:
+    --format <csv|json>           output format (default: csv)
+    --strength                    print the seasonal and trend strength instead of the components
+
+STL parameters:
+    --seasonal-length <n>         length of the seasonal smoother
+    --trend-length <n>            length of the trend smoother
+    --low-pass-length <n>         length of the low-pass filter
+    --seasonal-degree <n>         degree of locally-fitted polynomial in seasonal smoothing
+    --trend-degree <n>            degree of locally-fitted polynomial in trend smoothing
+    --low-pass-degree <n>         degree of locally-fitted polynomial in low-pass smoothing
+    --seasonal-jump <n>           skipping value for seasonal smoothing
+    --trend-jump <n>              skipping value for trend smoothing
+    --low-pass-jump <n>           skipping value for low-pass smoothing
+    --inner-loops <n>             number of loops for updating the seasonal and trend components
+    --outer-loops <n>             number of iterations of robust fitting
+    --robust                      use robustness iterations
+
+MSTL parameters (use MSTL even with a single period):
+    --iterations <n>              number of iterations
+    --lambda <x>                  lambda for Box-Cox transformation
+    --seasonal-lengths <n,...>    lengths of the seasonal smoothers
+
+    -h, --help                    print this help
+";
+
+#[derive(Debug, PartialEq)]
+enum Format {
+    Csv,
+    Json,
+}
+
+#[derive(Debug)]
+struct Options {
+    periods: Vec<usize>,
+    column: Option<String>,
+    delimiter: char,
+    header: Option<bool>,
+    format: Format,
+    strength: bool,
+    mstl: bool,
+    stl_params: StlParams,
+    mstl_params: MstlParams,
+    path: Option<String>,
+}
+
+#[derive(Debug)]
+struct Output {
+    columns: Vec<(String, Vec<f64>)>,
+    seasonal_strength: Vec<f64>,
+    trend_strength: f64,
+}
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("stlrs: {}", err);
+        process::exit(1);
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let args = env::args().skip(1).collect::<Vec<String>>();
+    if args.iter().any(|a| a == "-h" || a == "--help") {
+        print!("{}", USAGE);
+        return Ok(());
+    }
+
+    let options = parse_args(&args)?;
+
+    let input = match &options.path {
+        Some(path) => fs::read_to_string(path)?,
+        None => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+            input
+        }
+    };
+    let series = read_column(
+        &input,
+        options.column.as_deref(),
+        options.delimiter,
+        options.header,
+    )?;
+
+    let output = decompose(&series, &options)?;
+
+    let mut stdout = io::stdout().lock();
+    let text = match (&options.format, options.strength) {
+        (Format::Csv, false) => to_csv(&output),
+        (Format::Csv, true) => strength_csv(&output),
+        (Format::Json, false) => to_json(&output),
+        (Format::Json, true) => strength_json(&output),
+    };
+    stdout.write_all(text.as_bytes())?;
+    Ok(())
+}
+
+fn parse_args(args: &[String]) -> Result<Options, Box<dyn Error>> {
+    let mut options = Options {
+        periods: Vec::new(),
+        column: None,
+        delimiter: ',',
+        header: None,
+        format: Format::Csv,
+        strength: false,
+        mstl: false,
+        stl_params: StlParams::new(),
+        mstl_params: MstlParams::new(),
+        path: None,
+    };
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let (name, inline) = match arg.split_once('=') {
+            Some((name, value)) if arg.starts_with("--") => (name, Some(value.to_string())),
+            _ => (arg.as_str(), None),
+        };
+
+        if name == "--robust" {
+            options.stl_params.robust(true);
+            continue;
+        }
+        if name == "--no-header" {
+            options.header = Some(false);
+            continue;
+        }
+        if name == "--strength" {
+            options.strength = true;
+            continue;
+        }
+        if !name.starts_with('-') {
+            if options.path.is_some() {
+                return Err("only one input file can be given".into());
+            }
+            options.path = Some(arg.clone());
+            continue;
+        }
+
+        let value = match inline {
+            Some(value) => value,
+            None => args
+                .next()
+                .ok_or_else(|| format!("missing value for {}", name))?
+                .clone(),
+        };
+        let stl_params = &mut options.stl_params;
+
+        match name {
+            "--period" | "--periods" => options.periods.extend(parse_list(name, &value)?),
+            "--column" => options.column = Some(value),
+            "--delimiter" => {
+                let mut chars = value.chars();
+                options.delimiter = match (chars.next(), chars.next()) {
+                    (Some(c), None) => c,
+                    _ => return Err("delimiter must be a single character".into()),
+                };
+            }
+            "--format" => {
+                options.format = match value.as_str() {
+                    "csv" => Format::Csv,
+                    "json" => Format::Json,
+                    _ => return Err("format must be csv or json".into()),
+                };
+            }
+            "--seasonal-length" => {
+                stl_params.seasonal_length(parse(name, &value)?);
+            }
+            "--trend-length" => {
+                stl_params.trend_length(parse(name, &value)?);
+            }
+            "--low-pass-length" => {
+                stl_params.low_pass_length(parse(name, &value)?);
+            }
+            "--seasonal-degree" => {
+                stl_params.seasonal_degree(parse(name, &value)?);
+            }
+            "--trend-degree" => {
+                stl_params.trend_degree(parse(name, &value)?);
+            }
+            "--low-pass-degree" => {
+                stl_params.low_pass_degree(parse(name, &value)?);
+            }
+            "--seasonal-jump" => {
+                stl_params.seasonal_jump(parse(name, &value)?);
+            }
+            "--trend-jump" => {
+                stl_params.trend_jump(parse(name, &value)?);
+            }
+            "--low-pass-jump" => {
+                stl_params.low_pass_jump(parse(name, &value)?);
+            }
+            "--inner-loops" => {
+                stl_params.inner_loops(parse(name, &value)?);
+            }
+            "--outer-loops" => {
+                stl_params.outer_loops(parse(name, &value)?);
+            }
+            "--iterations" => {
+                options.mstl_params.iterations(parse(name, &value)?);
+                options.mstl = true;
+            }
+            "--lambda" => {
+                options.mstl_params.lambda(parse(name, &value)?);
+                options.mstl = true;
+            }
+            "--seasonal-lengths" => {
+                options
+                    .mstl_params
+                    .seasonal_lengths(&parse_list(name, &value)?);
+                options.mstl = true;
+            }
+            _ => return Err(format!("unknown option {}", name).into()),
+        }
+    }
+
+    if options.periods.is_empty() {
+        return Err("--period is required".into());
+    }
+
+    Ok(options)
+}
+
+fn parse<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, Box<dyn Error>> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid value for {}: {}", name, value).into())
+}
+
+fn parse_list(name: &str, value: &str) -> Result<Vec<usize>, Box<dyn Error>> {
+    value.split(',').map(|v| parse(name, v)).collect()
+}
+
+fn read_column(
+    input: &str,
+    column: Option<&str>,
+    delimiter: char,
+    header: Option<bool>,
+) -> Result<Vec<f64>, Box<dyn Error>> {
+    let mut rows = input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| split_row(line, delimiter))
+        .peekable();
+
+    let first = rows.peek().ok_or("input is empty")?;
+    // detect a header if the first row is not numeric
+    let header = header.unwrap_or_else(|| first.iter().any(|v| v.parse::<f64>().is_err()));
+
+    let index = match column {
+        Some(column) => {
+            let position = if header {
+                first.iter().position(|v| v == column)
+            } else {
+                None
+            };
+            match position {
+                Some(i) => i,
+                None => column
+                    .parse()
+                    .map_err(|_| format!("column not found: {}", column))?,
+            }
+        }
+        None => 0,
+    };
+
+    if header {
+        rows.next();
+    }
+
+    rows.enumerate()
+        .map(|(i, row)| {
+            let value = row
+                .get(index)
+                .ok_or_else(|| format!("row {} has no column {}", i + 1, index))?;
+            value
+                .parse()
+                .map_err(|_| format!("row {} has invalid number: {}", i + 1, value).into())
+        })
+        .collect()
+}
+
+fn split_row(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.trim_end_matches('\r').chars().peekable();
+    while let Some(c) = chars.next() {
+        if quoted {
+            if c == '"' {
+                if chars.peek() == Some(&'"') {
+                    field.push('"');
+                    chars.next();
+                } else {
+                    quoted = false;
+                }
+            } else {
+                field.push(c);
+            }
+        } else if c == '"' {
+            quoted = true;
+        } else if c == delimiter {
+            fields.push(field.trim().to_string());
+            field.clear();
+        } else {
+            field.push(c);
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
+fn decompose(series: &[f64], options: &Options) -> Result<Output, Box<dyn Error>> {
+    if options.periods.len() == 1 && !options.mstl {
+        let result = options.stl_params.fit(series, options.periods[0])?;
+        let seasonal_strength = vec![result.seasonal_strength()];
+        let trend_strength = result.trend_strength();
+        let (seasonal, trend, remainder, weights) = result.into_parts();
+        Ok(Output {
+            columns: vec![
+                ("seasonal".to_string(), seasonal),
+                ("trend".to_string(), trend),
+                ("remainder".to_string(), remainder),
+                ("weights".to_string(), weights),
+            ],
+            seasonal_strength,
+            trend_strength,
+        })
+    } else {
+        let mut params = options.mstl_params.clone();
+        params.stl_params(options.stl_params.clone());
+        let result = params.fit(series, &options.periods)?;
+        let seasonal_strength = result.seasonal_strength();
+        let trend_strength = result.trend_strength();
+        let (seasonal, trend, remainder) = result.into_parts();
+        let mut columns = options
+            .periods
+            .iter()
+            .zip(seasonal)
+            .map(|(np, s)| (format!("seasonal_{}", np), s))
+            .collect::<Vec<_>>();
+        columns.push(("trend".to_string(), trend));
+        columns.push(("remainder".to_string(), remainder));
+        Ok(Output {
+            columns,
+            seasonal_strength,
+            trend_strength,
+        })
+    }
+}
+
+fn join(values: &[f64]) -> String {
+    values
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+fn to_csv(output: &Output) -> String {
+    let mut csv = String::new();
+    let names = output
+        .columns
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<&str>>();
+    csv.push_str(&names.join(","));
+    csv.push('\n');
+    let n = output.columns[0].1.len();
+    for i in 0..n {
+        let row = output
+            .columns
+            .iter()
+            .map(|(_, values)| values[i])
+            .collect::<Vec<f64>>();
+        csv.push_str(&join(&row));
+        csv.push('\n');
+    }
+    csv
+}
+
+// one column per seasonal component, like seasonal_strength or seasonal_7_strength
+fn strength_csv(output: &Output) -> String {
+    let mut names = output
+        .columns
+        .iter()
+        .filter(|(name, _)| name.starts_with("seasonal"))
+        .map(|(name, _)| format!("{}_strength", name))
+        .collect::<Vec<String>>();
+    names.push("trend_strength".to_string());
+    let mut values = output.seasonal_strength.clone();
+    values.push(output.trend_strength);
+    format!("{}\n{}\n", names.join(","), join(&values))
+}
+
+fn json_number(v: f64) -> String {
+    if v.is_finite() {
+        v.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+fn json_array(values: &[f64]) -> String {
+    let values = values.iter().map(|v| json_number(*v)).collect::<Vec<_>>();
+    format!("[{}]", values.join(","))
+}
+
+fn to_json(output: &Output) -> String {
+    let mut json = String::from("{");
+    for (name, values) in &output.columns {
+        write!(json, "\"{}\":{},", name, json_array(values)).unwrap();
+    }
+    write!(
+        json,
+        "\"seasonal_strength\":{},\"trend_strength\":{}}}",
+        json_array(&output.seasonal_strength),
+        json_number(output.trend_strength)
+    )
+    .unwrap();
+    json.push('\n');
+    json
+}
+
+fn strength_json(output: &Output) -> String {
+    format!(
+        "{{\"seasonal_strength\":{},\"trend_strength\":{}}}\n",
+        json_array(&output.seasonal_strength),
+        json_number(output.trend_strength)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let options = parse_args(&args(&[
+            "--period=7",
+            "--robust",
+            "--trend-length",
+            "15",
+            "--format",
+            "json",
+            "data.csv",
+        ]))
+        .unwrap();
+        assert_eq!(vec![7], options.periods);
+        assert_eq!(Format::Json, options.format);
+        assert_eq!(Some("data.csv".to_string()), options.path);
+    }
+
+    #[test]
+    fn test_parse_args_periods() {
+        let options = parse_args(&args(&["--periods", "6,10", "--period", "12"])).unwrap();
+        assert_eq!(vec![6, 10, 12], options.periods);
+    }
+
+    #[test]
+    fn test_parse_args_missing_period() {
+        let err = parse_args(&args(&["--robust"])).unwrap_err();
+        assert_eq!("--period is required", err.to_string());
+    }
+
+    #[test]
+    fn test_parse_args_unknown() {
+        let err = parse_args(&args(&["--period", "7", "--foo", "1"])).unwrap_err();
+        assert_eq!("unknown option --foo", err.to_string());
+    }
+
+    #[test]
+    fn test_read_column() {
+        let input = "date,\"value, total\"\n2024-01-01,1.5\n2024-01-02,2\n";
+        let series = read_column(input, Some("value, total"), ',', None).unwrap();
+        assert_eq!(vec![1.5, 2.0], series);
+        let series = read_column(input, Some("1"), ',', None).unwrap();
+        assert_eq!(vec![1.5, 2.0], series);
+    }
+
+    #[test]
+    fn test_read_column_numbers() {
+        let series = read_column("1\n2\n\n3\n", None, ',', None).unwrap();
+        assert_eq!(vec![1.0, 2.0, 3.0], series);
+    }
+
+    #[test]
+    fn test_read_column_invalid() {
+        let err = read_column("1\nfoo\n", None, ',', Some(false)).unwrap_err();
+        assert_eq!("row 2 has invalid number: foo", err.to_string());
+    }
+
+    #[test]
+    fn test_decompose_mstl() {
+        let series = (0..30).map(|v| (v % 7) as f64).collect::<Vec<f64>>();
+        let options = parse_args(&args(&["--periods", "6,10"])).unwrap();
+        let output = decompose(&series, &options).unwrap();
+        let names = output
+            .columns
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(
+            vec!["seasonal_6", "seasonal_10", "trend", "remainder"],
+            names
+        );
+        assert_eq!(2, output.seasonal_strength.len());
+    }
+
+    #[test]
+    fn test_decompose_single_period_mstl() {
+        let series = (0..30).map(|v| (v % 7) as f64).collect::<Vec<f64>>();
+        let options = parse_args(&args(&["--period", "7", "--iterations", "3"])).unwrap();
+        let output = decompose(&series, &options).unwrap();
+        let names = output
+            .columns
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(vec!["seasonal_7", "trend", "remainder"], names);
+
+        let options = parse_args(&args(&["--period", "7", "--lambda", "1.5"])).unwrap();
+        let err = decompose(&series, &options).unwrap_err();
+        assert_eq!("lambda must be between 0 and 1, got 1.5", err.to_string());
+    }
+
+    #[test]
+    fn test_strength_csv() {
+        let series = (0..30).map(|v| (v % 7) as f64).collect::<Vec<f64>>();
+        let options = parse_args(&args(&["--period", "7", "--strength"])).unwrap();
+        assert!(options.strength);
+        let output = decompose(&series, &options).unwrap();
+        let csv = strength_csv(&output);
+        let mut lines = csv.lines();
+        assert_eq!(Some("seasonal_strength,trend_strength"), lines.next());
+        assert_eq!(2, lines.next().unwrap().split(',').count());
+        assert_eq!(None, lines.next());
+
+        let options = parse_args(&args(&["--periods", "6,10", "--strength"])).unwrap();
+        let output = decompose(&series, &options).unwrap();
+        assert!(strength_csv(&output)
+            .starts_with("seasonal_6_strength,seasonal_10_strength,trend_strength\n"));
+    }
+}
@@ -0,0 +1,614 @@
+use alloc::vec::Vec;
+
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, TimeZone, Timelike};
+
+use super::missing;
+use super::stl_params::ensure_finite;
+use super::{Error, Float, MstlParams, MstlResult};
+
+const SECONDS_PER_DAY: i64 = 86400;
+const DAYS_PER_LEAP_YEAR: i64 = 366;
+
+/// A calendar seasonality.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Seasonality {
+    /// Hour of the day in local time.
+    HourOfDay,
+    /// Day of the week.
+    DayOfWeek,
+    /// Day of the year, with days after February 28 aligned across leap years.
+    DayOfYear,
+}
+
+/// A MSTL result indexed by timestamp.
+#[derive(Clone, Debug)]
+pub struct MstlCalendarResult<T: Float, Tz: TimeZone> {
+    timestamps: Vec<DateTime<Tz>>,
+    seasonalities: Vec<Seasonality>,
+    periods: Vec<usize>,
+    seasonal: Vec<Vec<T>>,
+    trend: Vec<T>,
+    remainder: Vec<T>,
+}
+
+impl<T: Float, Tz: TimeZone> MstlCalendarResult<T, Tz> {
+    /// Returns the timestamps.
+    pub fn timestamps(&self) -> &[DateTime<Tz>] {
+        &self.timestamps
+    }
+
+    /// Returns the seasonalities.
+    pub fn seasonalities(&self) -> &[Seasonality] {
+        &self.seasonalities
+    }
+
+    /// Returns the periods used for the calendar grid.
+    pub fn periods(&self) -> &[usize] {
+        &self.periods
+    }
+
+    /// Returns the seasonal components.
+    pub fn seasonal(&self) -> &[Vec<T>] {
+        &self.seasonal
+    }
+
+    /// Returns the trend component.
+    pub fn trend(&self) -> &[T] {
+        &self.trend
+    }
+
+    /// Returns the remainder.
+    pub fn remainder(&self) -> &[T] {
+        &self.remainder
+    }
+}
+
+impl MstlParams {
+    /// Decomposes a time series with calendar seasonalities.
+    ///
+    /// Timestamps are mapped to phases using the local calendar, so seasonality
+    /// stays aligned across daylight saving time shifts and leap years. Gaps are
+    /// interpolated and repeated local times are averaged.
+    ///
+    /// Timestamps must be whole seconds. The series is decomposed on a regular grid
+    /// spanning the first to the last timestamp, so memory and time grow with the
+    /// span rather than the number of observations. Split series with long gaps.
+    ///
+    /// With both [`Seasonality::DayOfWeek`] and [`Seasonality::DayOfYear`], the day of
+    /// the year is fit on its own grid with a leap day every year, alternating with the
+    /// other seasonalities for the number of iterations. This does not support Box-Cox
+    /// transformations or Fourier terms for the day of the year.
+    pub fn fit_calendar<T: Float, Tz: TimeZone>(
+        &self,
+        timestamps: &[DateTime<Tz>],
+        series: &[T],
+        seasonalities: &[Seasonality],
+    ) -> Result<MstlCalendarResult<T, Tz>, Error> {
+        if timestamps.len() != series.len() {
+            return Err(Error::parameter(
+                "timestamps",
+                timestamps.len(),
+                "must have the same length as series",
+            ));
+        }
+        if seasonalities.is_empty() {
+            return Err(Error::parameter("seasonalities", 0, "must not be empty"));
+        }
+        if timestamps.windows(2).any(|w| w[0] >= w[1]) {
+            return Err(Error::InvalidColumn {
+                name: "timestamps",
+                reason: "must be increasing",
+            });
+        }
+        if self.check_finite {
+            ensure_finite(series)?;
+        }
+
+        let local = timestamps
+            .iter()
+            .map(|t| t.naive_local())
+            .collect::<Vec<_>>();
+        let step = step(&local)?;
+        let slots_per_day = SECONDS_PER_DAY / step;
+
+        // days of the week do not line up on a grid with a leap day every year, so with both,
+        // the grid follows the calendar and the day of the year gets its own grid
+        let by_year = seasonalities.contains(&Seasonality::DayOfYear);
+        let separate_years = by_year && seasonalities.contains(&Seasonality::DayOfWeek);
+        let periods = seasonalities
+            .iter()
+            .map(|s| match s {
+                Seasonality::HourOfDay if slots_per_day < 2 => Err(Error::InvalidColumn {
+                    name: "timestamps",
+                    reason: "must be less than a day apart for hour_of_day",
+                }),
+                Seasonality::HourOfDay => Ok(slots_per_day as usize),
+                Seasonality::DayOfWeek => Ok(7 * slots_per_day as usize),
+                Seasonality::DayOfYear => Ok((DAYS_PER_LEAP_YEAR * slots_per_day) as usize),
+            })
+            .collect::<Result<Vec<usize>, Error>>()?;
+
+        // place each observation on a regular grid of local calendar slots
+        let first_year = local[0].year();
+        let mut positions = Vec::with_capacity(local.len());
+        for t in &local {
+            let day = if by_year && !separate_years {
+                (t.year() - first_year) as i64 * DAYS_PER_LEAP_YEAR + leap_ordinal0(t.date())
+            } else {
+                t.date().num_days_from_ce() as i64
+            };
+            positions.push(day * slots_per_day + seconds_of_day(t) / step);
+        }
+        let start = positions[0];
+        for p in positions.iter_mut() {
+            *p -= start;
+        }
+
+        let len = positions[positions.len() - 1] as usize + 1;
+        let mut grid = alloc::vec![T::zero(); len];
+        let mut counts = alloc::vec![0usize; len];
+        for (&p, &v) in positions.iter().zip(series) {
+            grid[p as usize] += v;
+            counts[p as usize] += 1;
+        }
+        let mut valid = Vec::with_capacity(len);
+        for (i, (v, &c)) in grid.iter_mut().zip(&counts).enumerate() {
+            if c > 0 {
+                *v /= T::from_usize(c);
+                valid.push(i);
+            }
+        }
+        missing::interpolate(&mut grid, &valid)?;
+
+        let (grid_seasonal, grid_trend, grid_remainder) = if separate_years {
+            self.fit_separate_years(&grid, start, slots_per_day, seasonalities, &periods)?
+        } else {
+            self.fit(&grid, &periods)?
+        }
+        .into_parts();
+
+        let mut seasonal = Vec::with_capacity(periods.len());
+        for s in &grid_seasonal {
+            seasonal.push(positions.iter().map(|&p| s[p as usize]).collect());
+        }
+        let trend = positions.iter().map(|&p| grid_trend[p as usize]).collect();
+        // add back the difference from the averaged value for repeated local times
+        let remainder = positions
+            .iter()
+            .zip(series)
+            .map(|(&p, &v)| grid_remainder[p as usize] + v - grid[p as usize])
+            .collect();
+
+        Ok(MstlCalendarResult {
+            timestamps: timestamps.to_vec(),
+            seasonalities: seasonalities.to_vec(),
+            periods,
+            seasonal,
+            trend,
+            remainder,
+        })
+    }
+}
+
+impl MstlParams {
+    // backfits the day of the year on a grid with a leap day every year and the other
+    // seasonalities on the calendar grid, fitting the day of the year last so its trend is used,
+    // like the longest period in MSTL
+    fn fit_separate_years<T: Float>(
+        &self,
+        grid: &[T],
+        start: i64,
+        slots_per_day: i64,
+        seasonalities: &[Seasonality],
+        periods: &[usize],
+    ) -> Result<MstlResult<T>, Error> {
+        for (name, values) in [
+            ("seasonal_lengths", &self.swin),
+            ("fourier_terms", &self.fourier),
+        ] {
+            if let Some(values) = values {
+                if values.len() != periods.len() {
+                    return Err(Error::parameter(
+                        name,
+                        values.len(),
+                        "must have the same length as periods",
+                    ));
+                }
+            }
+        }
+        let year = seasonalities
+            .iter()
+            .position(|&s| s == Seasonality::DayOfYear)
+            .unwrap();
+        if self.fourier.as_ref().is_some_and(|f| f[year] > 0) {
+            return Err(Error::IncompatibleParameters {
+                name: "fourier_terms",
+                other: "seasonalities",
+            });
+        }
+        if self.lambda.is_some() {
+            return Err(Error::IncompatibleParameters {
+                name: "lambda",
+                other: "seasonalities",
+            });
+        }
+        if self.iterate == 0 {
+            return Err(Error::parameter("iterations", 0, "must be at least 1"));
+        }
+
+        let others = |values: &[usize]| {
+            values
+                .iter()
+                .enumerate()
+                .filter(|&(i, _)| i != year)
+                .map(|(_, &v)| v)
+                .collect::<Vec<usize>>()
+        };
+        let other_periods = others(periods);
+        let mut other_params = self.clone();
+        other_params.swin = self.swin.as_deref().map(others);
+        other_params.fourier = self.fourier.as_deref().map(others);
+        let mut year_params = self.clone();
+        year_params.swin = self.swin.as_ref().map(|s| alloc::vec![s[year]]);
+        year_params.fourier = None;
+        year_params.regressors = None;
+
+        // the position of each calendar slot on the grid for the day of the year
+        let first_year = date(start, slots_per_day).year();
+        let mut leap_positions = (0..grid.len() as i64)
+            .map(|i| {
+                let date = date(start + i, slots_per_day);
+                let day =
+                    (date.year() - first_year) as i64 * DAYS_PER_LEAP_YEAR + leap_ordinal0(date);
+                day * slots_per_day + (start + i).rem_euclid(slots_per_day)
+            })
+            .collect::<Vec<i64>>();
+        let leap_start = leap_positions[0];
+        for p in leap_positions.iter_mut() {
+            *p -= leap_start;
+        }
+        let valid = leap_positions
+            .iter()
+            .map(|&p| p as usize)
+            .collect::<Vec<usize>>();
+        let leap_len = valid[valid.len() - 1] + 1;
+
+        let n = grid.len();
+        let mut yearly = alloc::vec![T::zero(); n];
+        let mut parts = None;
+        for _ in 0..self.iterate {
+            let adjusted = grid
+                .iter()
+                .zip(&yearly)
+                .map(|(&v, &y)| v - y)
+                .collect::<Vec<T>>();
+            let fit = other_params.fit(&adjusted, &other_periods)?;
+
+            let mut leap_grid = alloc::vec![T::zero(); leap_len];
+            for (i, &p) in valid.iter().enumerate() {
+                let s = fit.seasonal().iter().map(|s| s[i]).sum::<T>();
+                let r = fit.regression().map_or(T::zero(), |r| r[i]);
+                leap_grid[p] = grid[i] - s - r;
+            }
+            missing::interpolate(&mut leap_grid, &valid)?;
+            let year_fit = year_params.fit(&leap_grid, &[periods[year]])?;
+            yearly = valid.iter().map(|&p| year_fit.seasonal()[0][p]).collect();
+            let trend = valid
+                .iter()
+                .map(|&p| year_fit.trend()[p])
+                .collect::<Vec<T>>();
+            parts = Some((fit, trend));
+        }
+
+        let (fit, trend) = parts.unwrap();
+        let mut seasonal = fit.seasonal;
+        let regression = fit.regression;
+        seasonal.insert(year, yearly);
+        let remainder = (0..n)
+            .map(|i| {
+                let s = seasonal.iter().map(|s| s[i]).sum::<T>();
+                let r = regression.as_ref().map_or(T::zero(), |r| r[i]);
+                grid[i] - s - r - trend[i]
+            })
+            .collect();
+        Ok(MstlResult {
+            seasonal,
+            trend,
+            remainder,
+            periods: periods.to_vec(),
+            regression,
+            coefficients: fit.coefficients,
+        })
+    }
+}
+
+fn date(slot: i64, slots_per_day: i64) -> NaiveDate {
+    NaiveDate::from_num_days_from_ce_opt(slot.div_euclid(slots_per_day) as i32).unwrap()
+}
+
+fn seconds_of_day(t: &NaiveDateTime) -> i64 {
+    t.num_seconds_from_midnight() as i64
+}
+
+// smallest positive spacing in local time, which must evenly divide a day
+fn step(local: &[NaiveDateTime]) -> Result<i64, Error> {
+    // sub-second spacing would truncate to zero seconds
+    if local.iter().any(|t| t.nanosecond() != 0) {
+        return Err(Error::InvalidColumn {
+            name: "timestamps",
+            reason: "must be whole seconds",
+        });
+    }
+    let step = local
+        .windows(2)
+        .map(|w| (w[1] - w[0]).num_seconds())
+        .filter(|&d| d > 0)
+        .min()
+        .ok_or(Error::SeriesTooShort {
+            len: local.len(),
+            min_len: 2,
+        })?;
+    let step = step.min(SECONDS_PER_DAY);
+    if SECONDS_PER_DAY % step != 0 {
+        return Err(Error::InvalidColumn {
+            name: "timestamps",
+            reason: "must be evenly spaced within a day",
+        });
+    }
+    if local
+        .iter()
+        .any(|t| seconds_of_day(t) % step != seconds_of_day(&local[0]) % step)
+    {
+        return Err(Error::InvalidColumn {
+            name: "timestamps",
+            reason: "must be evenly spaced within a day",
+        });
+    }
+    Ok(step)
+}
+
+// day of the year in a leap year, so days after February 28 line up across years
+fn leap_ordinal0(date: NaiveDate) -> i64 {
+    let ordinal0 = date.ordinal0() as i64;
+    let leap = NaiveDate::from_ymd_opt(date.year(), 2, 29).is_some();
+    if !leap && date.month() > 2 {
+        ordinal0 + 1
+    } else {
+        ordinal0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stl::test_helpers::*;
+    use crate::{Error, Mstl, Seasonality};
+    use alloc::vec::Vec;
+    use chrono::{DateTime, Datelike, Duration, FixedOffset, TimeZone, Timelike, Utc};
+
+    fn hour_effect(hour: u32) -> f64 {
+        ((hour * 7) % 24) as f64 / 4.0
+    }
+
+    fn generate_series(n: usize) -> Vec<f64> {
+        (0..n)
+            .map(|i| {
+                let day = ((i / 24) % 7) as f64;
+                10.0 + hour_effect((i % 24) as u32) + day * 0.5 + (i % 5) as f64 * 0.1
+            })
+            .collect()
+    }
+
+    fn hourly(n: usize) -> Vec<DateTime<Utc>> {
+        let start = Utc.with_ymd_and_hms(2026, 1, 5, 0, 0, 0).unwrap();
+        (0..n).map(|i| start + Duration::hours(i as i64)).collect()
+    }
+
+    #[test]
+    fn test_regular() {
+        let timestamps = hourly(24 * 21);
+        let series = generate_series(timestamps.len());
+        let result = Mstl::params()
+            .fit_calendar(
+                &timestamps,
+                &series,
+                &[Seasonality::HourOfDay, Seasonality::DayOfWeek],
+            )
+            .unwrap();
+        assert_eq!(&[24, 168], result.periods());
+        assert_eq!(timestamps, result.timestamps());
+
+        let expected = Mstl::fit(&series, &[24, 168]).unwrap();
+        assert_elements_in_delta(&expected.seasonal()[0], &result.seasonal()[0]);
+        assert_elements_in_delta(expected.trend(), result.trend());
+        assert_elements_in_delta(expected.remainder(), result.remainder());
+    }
+
+    #[test]
+    fn test_dst() {
+        // hourly readings in local time across the end of daylight saving time,
+        // where 1 AM occurs twice
+        let edt = FixedOffset::west_opt(4 * 3600).unwrap();
+        let est = FixedOffset::west_opt(5 * 3600).unwrap();
+        let start = edt.with_ymd_and_hms(2026, 10, 12, 0, 0, 0).unwrap();
+        let shift = Utc.with_ymd_and_hms(2026, 11, 1, 6, 0, 0).unwrap();
+        let timestamps = (0..24 * 42)
+            .map(|i| {
+                let t = start + Duration::hours(i);
+                t.with_timezone(if t < shift { &edt } else { &est })
+            })
+            .collect::<Vec<_>>();
+        let series = timestamps
+            .iter()
+            .map(|t| 10.0 + hour_effect(t.hour()))
+            .collect::<Vec<f64>>();
+
+        let result = Mstl::params()
+            .fit_calendar(&timestamps, &series, &[Seasonality::HourOfDay])
+            .unwrap();
+        let seasonal = &result.seasonal()[0];
+        assert_eq!(series.len(), seasonal.len());
+
+        // the seasonal component follows the local hour on both sides of the shift
+        let mean = (0..24).map(hour_effect).sum::<f64>() / 24.0;
+        for (i, t) in timestamps.iter().enumerate().skip(24 * 7).take(24 * 28) {
+            assert!((seasonal[i] - (hour_effect(t.hour()) - mean)).abs() < 0.1);
+        }
+        for (i, &v) in series.iter().enumerate() {
+            assert_in_delta(v, seasonal[i] + result.trend()[i] + result.remainder()[i]);
+        }
+    }
+
+    #[test]
+    fn test_day_of_year() {
+        let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+        let timestamps = (0..365 * 3 + 1)
+            .map(|i| start + Duration::days(i))
+            .collect::<Vec<_>>();
+        let series = timestamps
+            .iter()
+            .map(|t| (t.month() % 6) as f64)
+            .collect::<Vec<f64>>();
+        let result = Mstl::params()
+            .fit_calendar(&timestamps, &series, &[Seasonality::DayOfYear])
+            .unwrap();
+        assert_eq!(&[366], result.periods());
+        assert_eq!(series.len(), result.trend().len());
+
+        // March 1 has the same phase in leap and non-leap years
+        let march = timestamps
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.month() == 3 && t.day() == 1)
+            .map(|(i, _)| result.seasonal()[0][i])
+            .collect::<Vec<f64>>();
+        assert_eq!(3, march.len());
+        assert!((march[0] - march[1]).abs() < 0.1);
+        assert!((march[1] - march[2]).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_hour_of_day_daily() {
+        let start = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        let timestamps = (0..60)
+            .map(|i| start + Duration::days(i))
+            .collect::<Vec<_>>();
+        let series = generate_series(60);
+        let result = Mstl::params().fit_calendar(&timestamps, &series, &[Seasonality::HourOfDay]);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::InvalidColumn {
+                name: "timestamps",
+                reason: "must be less than a day apart for hour_of_day"
+            }
+        );
+    }
+
+    fn weekly_yearly(timestamps: &[DateTime<Utc>]) -> Vec<f64> {
+        timestamps
+            .iter()
+            .map(|t| {
+                let week = [0.0, 1.0, 2.0, 3.0, 2.0, 1.0, -3.0];
+                let year = (t.ordinal0() as f64 - 183.0).abs() / 20.0;
+                10.0 + week[t.weekday().num_days_from_monday() as usize] + year
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_day_of_week_and_year() {
+        let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+        let timestamps = (0..365 * 3 + 1)
+            .map(|i| start + Duration::days(i))
+            .collect::<Vec<_>>();
+        let series = weekly_yearly(&timestamps);
+        let result = Mstl::params()
+            .fit_calendar(
+                &timestamps,
+                &series,
+                &[Seasonality::DayOfWeek, Seasonality::DayOfYear],
+            )
+            .unwrap();
+        assert_eq!(&[7, 366], result.periods());
+        for (i, &v) in series.iter().enumerate() {
+            let seasonal = result.seasonal()[0][i] + result.seasonal()[1][i];
+            assert_in_delta(v, seasonal + result.trend()[i] + result.remainder()[i]);
+        }
+
+        // the weekly pattern is recovered apart from its mean
+        let weekly = &result.seasonal()[0];
+        let monday = timestamps[500..507]
+            .iter()
+            .position(|t| t.weekday().num_days_from_monday() == 0)
+            .unwrap()
+            + 500;
+        let expected = [0.0, 1.0, 2.0, 3.0, 2.0, 1.0, -3.0];
+        for d in 1..7 {
+            let diff = weekly[monday + d] - weekly[monday];
+            assert!((diff - expected[d]).abs() < 0.5);
+        }
+
+        // March 1 has the same phase in leap and non-leap years
+        let march = timestamps
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.month() == 3 && t.day() == 1)
+            .map(|(i, _)| result.seasonal()[1][i])
+            .collect::<Vec<f64>>();
+        assert_eq!(3, march.len());
+        assert!((march[0] - march[1]).abs() < 0.5);
+        assert!((march[1] - march[2]).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_day_of_week_and_year_fourier() {
+        let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+        let timestamps = (0..365 * 3 + 1)
+            .map(|i| start + Duration::days(i))
+            .collect::<Vec<_>>();
+        let series = weekly_yearly(&timestamps);
+        let result = Mstl::params().fourier_terms(&[0, 3]).fit_calendar(
+            &timestamps,
+            &series,
+            &[Seasonality::DayOfWeek, Seasonality::DayOfYear],
+        );
+        assert_eq!(
+            result.unwrap_err(),
+            Error::IncompatibleParameters {
+                name: "fourier_terms",
+                other: "seasonalities"
+            }
+        );
+    }
+
+    #[test]
+    fn test_unordered() {
+        let mut timestamps = hourly(24 * 21);
+        timestamps.swap(3, 4);
+        let series = generate_series(timestamps.len());
+        let result = Mstl::params().fit_calendar(&timestamps, &series, &[Seasonality::HourOfDay]);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::InvalidColumn {
+                name: "timestamps",
+                reason: "must be increasing"
+            }
+        );
+    }
+
+    #[test]
+    fn test_sub_second() {
+        let start = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        let timestamps = (0..60)
+            .map(|i| start + Duration::milliseconds(500 * i))
+            .collect::<Vec<_>>();
+        let series = generate_series(60);
+        let result = Mstl::params().fit_calendar(&timestamps, &series, &[Seasonality::HourOfDay]);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::InvalidColumn {
+                name: "timestamps",
+                reason: "must be whole seconds"
+            }
+        );
+    }
+}
@@ -0,0 +1,503 @@
+// Killick, R., Fearnhead, P., & Eckley, I. A. (2012).
+// Optimal Detection of Changepoints With a Linear Computational Cost.
+// Journal of the American Statistical Association, 107(500), 1590-1598.
+
+use alloc::{vec, vec::Vec};
+
+use super::{Error, Float};
+
+/// A changepoint search method.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ChangepointMethod {
+    /// Pruned exact linear time.
+    Pelt,
+    /// Binary segmentation.
+    BinarySegmentation,
+}
+
+/// A segment model.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ChangepointModel {
+    /// A constant level in each segment, for level shifts.
+    Mean,
+    /// A line in each segment, for level shifts and slope changes.
+    Linear,
+}
+
+/// A set of changepoint parameters.
+#[derive(Clone, Debug)]
+pub struct ChangepointParams {
+    method: ChangepointMethod,
+    model: ChangepointModel,
+    penalty: Option<f64>,
+    min_size: usize,
+}
+
+/// A segment between changepoints.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Segment {
+    /// The index of the first point.
+    pub start: usize,
+    /// The index after the last point.
+    pub end: usize,
+    /// The mean.
+    pub mean: f64,
+    /// The least squares slope.
+    pub slope: f64,
+}
+
+/// A changepoint result.
+#[derive(Clone, Debug)]
+pub struct ChangepointResult {
+    changepoints: Vec<usize>,
+    segments: Vec<Segment>,
+}
+
+impl ChangepointParams {
+    /// Creates a new set of parameters.
+    pub fn new() -> Self {
+        Self {
+            method: ChangepointMethod::Pelt,
+            model: ChangepointModel::Linear,
+            penalty: None,
+            min_size: 3,
+        }
+    }
+
+    /// Sets the search method.
+    pub fn method(&mut self, method: ChangepointMethod) -> &mut Self {
+        self.method = method;
+        self
+    }
+
+    /// Sets the segment model.
+    pub fn model(&mut self, model: ChangepointModel) -> &mut Self {
+        self.model = model;
+        self
+    }
+
+    /// Sets the penalty for adding a changepoint, in units of the noise variance.
+    ///
+    /// Defaults to BIC with the `std` feature and must be set without it.
+    pub fn penalty(&mut self, penalty: f64) -> &mut Self {
+        self.penalty = Some(penalty);
+        self
+    }
+
+    /// Sets the minimum number of points in a segment.
+    pub fn min_size(&mut self, min_size: usize) -> &mut Self {
+        self.min_size = min_size;
+        self
+    }
+
+    /// Detects changepoints in a series.
+    pub fn fit<T: Float>(&self, series: &[T]) -> Result<ChangepointResult, Error> {
+        if self.min_size < 2 {
+            return Err(Error::parameter(
+                "min_size",
+                self.min_size,
+                "must be at least 2",
+            ));
+        }
+
+        if let Some(penalty) = self.penalty {
+            if penalty.is_nan() || penalty < 0.0 {
+                return Err(Error::float_parameter(
+                    "penalty",
+                    penalty,
+                    "must be non-negative",
+                ));
+            }
+        }
+
+        let n = series.len();
+        if n < self.min_size {
+            return Err(Error::SeriesTooShort {
+                len: n,
+                min_len: self.min_size,
+            });
+        }
+
+        let cost = Cost::new(series, self.model);
+        let penalty = match self.penalty {
+            Some(penalty) => penalty,
+            None => self.default_penalty(n)?,
+        };
+
+        let changepoints = match self.method {
+            ChangepointMethod::Pelt => pelt(&cost, n, penalty, self.min_size),
+            ChangepointMethod::BinarySegmentation => {
+                let mut changepoints = Vec::new();
+                binseg(&cost, 0, n, penalty, self.min_size, &mut changepoints);
+                changepoints.sort_unstable();
+                changepoints
+            }
+        };
+
+        let mut segments = Vec::with_capacity(changepoints.len() + 1);
+        let mut start = 0;
+        for &end in changepoints.iter().chain(&[n]) {
+            segments.push(cost.segment(start, end));
+            start = end;
+        }
+
+        Ok(ChangepointResult {
+            changepoints,
+            segments,
+        })
+    }
+
+    // BIC
+    #[cfg(feature = "std")]
+    fn default_penalty(&self, n: usize) -> Result<f64, Error> {
+        let params = match self.model {
+            ChangepointModel::Mean => 1.0,
+            ChangepointModel::Linear => 2.0,
+        };
+        Ok((params + 1.0) * (n as f64).ln())
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn default_penalty(&self, _n: usize) -> Result<f64, Error> {
+        Err(Error::MissingParameter { name: "penalty" })
+    }
+}
+
+impl Default for ChangepointParams {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChangepointResult {
+    /// Returns the changepoints, which are the indices where new segments start.
+    pub fn changepoints(&self) -> &[usize] {
+        &self.changepoints
+    }
+
+    /// Returns the segments.
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+}
+
+// segment costs in constant time using cumulative sums
+struct Cost {
+    model: ChangepointModel,
+    scale: f64,
+    sx: Vec<f64>,
+    sxx: Vec<f64>,
+    st: Vec<f64>,
+    stt: Vec<f64>,
+    stx: Vec<f64>,
+}
+
+impl Cost {
+    fn new<T: Float>(series: &[T], model: ChangepointModel) -> Self {
+        let n = series.len();
+        let mut sx = vec![0.0; n + 1];
+        let mut sxx = vec![0.0; n + 1];
+        let mut st = vec![0.0; n + 1];
+        let mut stt = vec![0.0; n + 1];
+        let mut stx = vec![0.0; n + 1];
+        for (i, v) in series.iter().enumerate() {
+            let x = (*v).as_f64();
+            let t = i as f64;
+            sx[i + 1] = sx[i] + x;
+            sxx[i + 1] = sxx[i] + x * x;
+            st[i + 1] = st[i] + t;
+            stt[i + 1] = stt[i] + t * t;
+            stx[i + 1] = stx[i] + t * x;
+        }
+
+        let variance = noise_variance(series);
+        let scale = if variance > 0.0 { 1.0 / variance } else { 1.0 };
+
+        Self {
+            model,
+            scale,
+            sx,
+            sxx,
+            st,
+            stt,
+            stx,
+        }
+    }
+
+    // returns the centered sums of squares and cross products for [a, b)
+    fn moments(&self, a: usize, b: usize) -> (f64, f64, f64, f64) {
+        let m = (b - a) as f64;
+        let sx = self.sx[b] - self.sx[a];
+        let st = self.st[b] - self.st[a];
+        let xx = (self.sxx[b] - self.sxx[a]) - sx * sx / m;
+        let tt = (self.stt[b] - self.stt[a]) - st * st / m;
+        let tx = (self.stx[b] - self.stx[a]) - st * sx / m;
+        (sx / m, xx, tt, tx)
+    }
+
+    fn cost(&self, a: usize, b: usize) -> f64 {
+        let (_, xx, tt, tx) = self.moments(a, b);
+        let rss = match self.model {
+            ChangepointModel::Mean => xx,
+            ChangepointModel::Linear => {
+                if tt > 0.0 {
+                    xx - tx * tx / tt
+                } else {
+                    xx
+                }
+            }
+        };
+        rss.max(0.0) * self.scale
+    }
+
+    fn segment(&self, start: usize, end: usize) -> Segment {
+        let (mean, _, tt, tx) = self.moments(start, end);
+        Segment {
+            start,
+            end,
+            mean,
+            slope: if tt > 0.0 { tx / tt } else { 0.0 },
+        }
+    }
+}
+
+// robust estimate of the noise variance from the median absolute deviation of differences
+fn noise_variance<T: Float>(series: &[T]) -> f64 {
+    if series.len() < 3 {
+        return 0.0;
+    }
+    let mut diffs = series
+        .windows(2)
+        .map(|w| (w[1] - w[0]).as_f64())
+        .collect::<Vec<f64>>();
+    let med = median(&mut diffs);
+    for d in diffs.iter_mut() {
+        *d = (*d - med).abs();
+    }
+    let mad = median(&mut diffs);
+    let sigma = 1.4826 * mad;
+    sigma * sigma / 2.0
+}
+
+fn median(x: &mut [f64]) -> f64 {
+    x.sort_unstable_by(|a, b| a.total_cmp(b));
+    let n = x.len();
+    (x[(n - 1) / 2] + x[n / 2]) / 2.0
+}
+
+fn pelt(cost: &Cost, n: usize, penalty: f64, min_size: usize) -> Vec<usize> {
+    let mut f = vec![f64::INFINITY; n + 1];
+    let mut last = vec![0; n + 1];
+    let mut candidates = vec![0];
+    f[0] = -penalty;
+
+    for t in min_size..=n {
+        let s = t - min_size;
+        if s > 0 && f[s].is_finite() {
+            candidates.push(s);
+        }
+
+        let mut costs = Vec::with_capacity(candidates.len());
+        for &s in &candidates {
+            let c = f[s] + cost.cost(s, t);
+            costs.push(c);
+            if c + penalty < f[t] {
+                f[t] = c + penalty;
+                last[t] = s;
+            }
+        }
+
+        // prune candidates that can never be optimal
+        let ft = f[t];
+        let mut i = 0;
+        candidates.retain(|_| {
+            let keep = costs[i] <= ft;
+            i += 1;
+            keep
+        });
+    }
+
+    let mut changepoints = Vec::new();
+    let mut t = last[n];
+    while t > 0 {
+        changepoints.push(t);
+        t = last[t];
+    }
+    changepoints.reverse();
+    changepoints
+}
+
+fn binseg(
+    cost: &Cost,
+    a: usize,
+    b: usize,
+    penalty: f64,
+    min_size: usize,
+    changepoints: &mut Vec<usize>,
+) {
+    if b - a < 2 * min_size {
+        return;
+    }
+
+    let total = cost.cost(a, b);
+    let mut best = None;
+    let mut best_gain = penalty;
+    for s in a + min_size..=b - min_size {
+        let gain = total - cost.cost(a, s) - cost.cost(s, b);
+        if gain > best_gain {
+            best_gain = gain;
+            best = Some(s);
+        }
+    }
+
+    if let Some(s) = best {
+        changepoints.push(s);
+        binseg(cost, a, s, penalty, min_size, changepoints);
+        binseg(cost, s, b, penalty, min_size, changepoints);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stl::test_helpers::*;
+    use crate::{
+        ChangepointMethod, ChangepointModel, ChangepointParams, Error, Mstl, ParameterValue, Stl,
+    };
+    use alloc::vec::Vec;
+
+    fn generate_series() -> Vec<f64> {
+        (0..98)
+            .map(|i| {
+                let seasonal = [3.0, -1.0, 0.5, 2.0, -2.0, -1.5, -1.0][i % 7];
+                let noise = ((i * 37 % 11) as f64 - 5.0) / 10.0;
+                let level = if i >= 49 { 10.0 } else { 0.0 };
+                seasonal + noise + level
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_pelt() {
+        let result = Stl::params()
+            .robust(true)
+            .fit(&generate_series(), 7)
+            .unwrap();
+        let changepoints = result
+            .changepoints(ChangepointParams::new().penalty(15.0))
+            .unwrap();
+        assert_eq!(&[49], changepoints.changepoints());
+
+        let segments = changepoints.segments();
+        assert_eq!(2, segments.len());
+        assert_eq!((0, 49), (segments[0].start, segments[0].end));
+        assert_eq!((49, 98), (segments[1].start, segments[1].end));
+        assert_in_delta(-0.014743897762336852, segments[0].mean);
+        assert_in_delta(10.011306558356829, segments[1].mean);
+    }
+
+    #[test]
+    fn test_binary_segmentation() {
+        let result = Stl::params()
+            .robust(true)
+            .fit(&generate_series(), 7)
+            .unwrap();
+        let changepoints = result
+            .changepoints(
+                ChangepointParams::new()
+                    .method(ChangepointMethod::BinarySegmentation)
+                    .model(ChangepointModel::Mean)
+                    .penalty(15.0),
+            )
+            .unwrap();
+        assert_eq!(&[49], changepoints.changepoints());
+    }
+
+    #[test]
+    fn test_mstl() {
+        let mut stl_params = Stl::params();
+        stl_params.robust(true);
+        let result = Mstl::params()
+            .stl_params(stl_params)
+            .fit(&generate_series(), &[7])
+            .unwrap();
+        let changepoints = result
+            .changepoints(ChangepointParams::new().penalty(15.0))
+            .unwrap();
+        assert_eq!(&[49], changepoints.changepoints());
+    }
+
+    #[test]
+    fn test_slope_change() {
+        let series = (0..100)
+            .map(|i| {
+                let noise = ((i * 37 % 11) as f64 - 5.0) / 10.0;
+                let trend = if i < 60 {
+                    0.1 * i as f64
+                } else {
+                    6.0 - 0.3 * (i - 60) as f64
+                };
+                trend + noise
+            })
+            .collect::<Vec<f64>>();
+        let changepoints = ChangepointParams::new().penalty(15.0).fit(&series).unwrap();
+        assert_eq!(&[60], changepoints.changepoints());
+
+        let segments = changepoints.segments();
+        assert_in_delta(0.10019171992220065, segments[0].slope);
+        assert_in_delta(-0.30161350844277696, segments[1].slope);
+    }
+
+    #[test]
+    fn test_default_penalty() {
+        let result = Stl::params()
+            .robust(true)
+            .fit(&generate_series(), 7)
+            .unwrap();
+        let changepoints = result.changepoints(&ChangepointParams::new());
+        if cfg!(feature = "std") {
+            assert_eq!(&[49], changepoints.unwrap().changepoints());
+        } else {
+            assert_eq!(
+                changepoints.unwrap_err(),
+                Error::MissingParameter { name: "penalty" }
+            );
+        }
+    }
+
+    #[test]
+    fn test_bad_min_size() {
+        let result = ChangepointParams::new().min_size(1).fit(&generate_series());
+        assert_eq!(
+            result.unwrap_err(),
+            Error::InvalidParameter {
+                name: "min_size",
+                value: ParameterValue::Integer(1),
+                reason: "must be at least 2",
+            }
+        );
+    }
+
+    #[test]
+    fn test_bad_penalty() {
+        let result = ChangepointParams::new()
+            .penalty(-1.0)
+            .fit(&generate_series());
+        assert_eq!(
+            result.unwrap_err(),
+            Error::InvalidParameter {
+                name: "penalty",
+                value: ParameterValue::Float(-1.0),
+                reason: "must be non-negative",
+            }
+        );
+    }
+
+    #[test]
+    fn test_too_short() {
+        let result = ChangepointParams::new().fit(&[1.0, 2.0]);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::SeriesTooShort { len: 2, min_len: 3 }
+        );
+    }
+}
@@ -0,0 +1,233 @@
+// Classical decomposition by moving averages, like `decompose` in R.
+
+use alloc::{vec, vec::Vec};
+
+use super::stl_impl::ma;
+use super::stl_params::ensure_finite;
+use super::{Error, Float, StlResult};
+
+/// Classical decomposition by moving averages.
+pub struct Classical;
+
+/// A classical decomposition model.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ClassicalModel {
+    /// The components are added together.
+    Additive,
+    /// The components are multiplied together.
+    ///
+    /// Strengths and features assume additive components, so they do not apply to this model.
+    Multiplicative,
+}
+
+/// A set of classical decomposition parameters.
+#[derive(Clone, Debug)]
+pub struct ClassicalParams {
+    pub(crate) model: ClassicalModel,
+}
+
+impl Classical {
+    /// Decomposes a time series with an additive model.
+    pub fn fit<T: Float>(series: &[T], period: usize) -> Result<StlResult<T>, Error> {
+        ClassicalParams::new().fit(series, period)
+    }
+
+    /// Creates a new set of parameters.
+    pub fn params() -> ClassicalParams {
+        ClassicalParams::new()
+    }
+}
+
+impl ClassicalParams {
+    /// Creates a new set of parameters.
+    pub fn new() -> Self {
+        Self {
+            model: ClassicalModel::Additive,
+        }
+    }
+
+    /// Sets the model.
+    pub fn model(&mut self, model: ClassicalModel) -> &mut Self {
+        self.model = model;
+        self
+    }
+
+    /// Decomposes a time series.
+    ///
+    /// The trend is a centered moving average, so it is NaN for the first and last half period.
+    /// The remainder is NaN at the same points, and the weights are all one.
+    /// Strengths and features skip these points.
+    pub fn fit<T: Float>(&self, series: &[T], period: usize) -> Result<StlResult<T>, Error> {
+        if period < 2 {
+            return Err(Error::parameter("period", period, "must be at least 2"));
+        }
+
+        let n = series.len();
+        if n / 2 < period {
+            return Err(Error::SeriesTooShort {
+                len: n,
+                min_len: 2 * period,
+            });
+        }
+
+        ensure_finite(series)?;
+
+        // a 2 x period moving average for even periods
+        let mut trend = vec![T::from_f64(f64::NAN); n];
+        let mut ave = vec![T::zero(); n - period + 1];
+        ma(series, n, period, &mut ave);
+        let half = period / 2;
+        if period % 2 == 0 {
+            let mut centered = vec![T::zero(); n - period];
+            ma(&ave, n - period + 1, 2, &mut centered);
+            trend[half..n - half].copy_from_slice(&centered);
+        } else {
+            trend[half..n - half].copy_from_slice(&ave);
+        }
+
+        let detrend = |v: T, t: T| match self.model {
+            ClassicalModel::Additive => v - t,
+            ClassicalModel::Multiplicative => v / t,
+        };
+
+        let mut sums = vec![T::zero(); period];
+        let mut counts = vec![0; period];
+        for i in half..n - half {
+            sums[i % period] += detrend(series[i], trend[i]);
+            counts[i % period] += 1;
+        }
+        let mut figure = sums
+            .iter()
+            .zip(&counts)
+            .map(|(&s, &c)| s / T::from_usize(c))
+            .collect::<Vec<T>>();
+        let center = figure.iter().copied().sum::<T>() / T::from_usize(period);
+        for f in &mut figure {
+            *f = detrend(*f, center);
+        }
+
+        let seasonal = (0..n).map(|i| figure[i % period]).collect::<Vec<T>>();
+        let remainder = series
+            .iter()
+            .zip(&seasonal)
+            .zip(&trend)
+            .map(|((&v, &s), &t)| match self.model {
+                ClassicalModel::Additive => v - s - t,
+                ClassicalModel::Multiplicative => v / (s * t),
+            })
+            .collect();
+
+        Ok(StlResult {
+            seasonal,
+            trend,
+            remainder,
+            weights: vec![T::one(); n],
+            period,
+        })
+    }
+}
+
+impl Default for ClassicalParams {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stl::test_helpers::*;
+    use crate::{Classical, ClassicalModel, Error, ParameterValue};
+    use alloc::{vec, vec::Vec};
+
+    fn generate_series() -> Vec<f32> {
+        vec![
+            5.0, 9.0, 2.0, 9.0, 0.0, 6.0, 3.0, 8.0, 5.0, 8.0, 7.0, 8.0, 8.0, 0.0, 2.0, 5.0, 0.0,
+            5.0, 6.0, 7.0, 3.0, 6.0, 1.0, 4.0, 4.0, 4.0, 3.0, 7.0, 5.0, 8.0,
+        ]
+    }
+
+    #[test]
+    fn test_additive() {
+        let result = Classical::fit(&generate_series(), 7).unwrap();
+        assert!(result.trend()[..3].iter().all(|v| v.is_nan()));
+        assert!(result.trend()[27..].iter().all(|v| v.is_nan()));
+        assert_elements_in_delta(
+            &[4.857143, 5.285714, 4.714286, 5.571429, 5.285714],
+            &result.trend()[3..8],
+        );
+        assert_elements_in_delta(
+            &[0.967687, -0.889456, -0.413265, 1.634354, -0.115646],
+            &result.seasonal()[..5],
+        );
+        assert_in_delta(0.0, result.seasonal()[..7].iter().sum());
+        assert_in_delta(2.508503, result.remainder()[3]);
+        assert!(result.remainder()[0].is_nan());
+    }
+
+    #[test]
+    fn test_strength() {
+        let result = Classical::fit(&generate_series(), 7).unwrap();
+        let seasonal_strength = result.seasonal_strength();
+        let trend_strength = result.trend_strength();
+        assert!(seasonal_strength > 0.0 && seasonal_strength < 1.0);
+        assert!(trend_strength > 0.0 && trend_strength < 1.0);
+
+        let features = result.features();
+        assert_eq!(seasonal_strength, features.seasonal_strength);
+        assert!(features.spikiness.is_finite());
+        assert!(features.linearity.is_finite());
+        assert!(features.remainder_acf1.is_finite());
+    }
+
+    #[test]
+    fn test_even_period() {
+        let result = Classical::fit(&generate_series(), 6).unwrap();
+        assert!(result.trend()[2].is_nan());
+        assert!(result.trend()[27].is_nan());
+        assert_elements_in_delta(&[5.0, 4.75, 4.916667], &result.trend()[3..6]);
+    }
+
+    #[test]
+    fn test_multiplicative() {
+        let series = generate_series()
+            .iter()
+            .map(|v| v + 1.0)
+            .collect::<Vec<f32>>();
+        let result = Classical::params()
+            .model(ClassicalModel::Multiplicative)
+            .fit(&series, 7)
+            .unwrap();
+        assert_in_delta(7.0, result.seasonal()[..7].iter().sum());
+        for (i, &v) in series.iter().enumerate().take(27).skip(3) {
+            assert_in_delta(
+                v,
+                result.seasonal()[i] * result.trend()[i] * result.remainder()[i],
+            );
+        }
+    }
+
+    #[test]
+    fn test_too_short() {
+        let result = Classical::fit(&generate_series(), 16);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::SeriesTooShort {
+                len: 30,
+                min_len: 32
+            }
+        );
+    }
+
+    #[test]
+    fn test_period_one() {
+        let result = Classical::fit(&generate_series(), 1);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::InvalidParameter {
+                name: "period",
+                value: ParameterValue::Integer(1),
+                reason: "must be at least 2",
+            }
+        );
+    }
+}
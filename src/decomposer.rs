@@ -0,0 +1,282 @@
+use alloc::{vec, vec::Vec};
+
+use super::{
+    ClassicalModel, ClassicalParams, Error, Float, MstlParams, MstlResult, ParameterValue,
+    StlParams, StlResult, StrParams, StrResult,
+};
+
+#[cfg(feature = "std")]
+use super::RobustStlParams;
+
+/// A decomposition method.
+pub trait Decomposer {
+    /// The result of a decomposition.
+    type Output<T: Float>: Decomposition<T>;
+
+    /// Decomposes a time series.
+    fn decompose<T: Float>(
+        &self,
+        series: &[T],
+        periods: &[usize],
+    ) -> Result<Self::Output<T>, Error>;
+}
+
+/// The components of a decomposition.
+pub trait Decomposition<T: Float> {
+    /// Returns the seasonal components, in the same order as the periods.
+    fn seasonal_components(&self) -> Vec<&[T]>;
+
+    /// Returns the trend component.
+    fn trend(&self) -> &[T];
+
+    /// Returns the remainder.
+    fn remainder(&self) -> &[T];
+
+    /// Returns the strength of each seasonal component.
+    fn seasonal_strengths(&self) -> Vec<f64>;
+
+    /// Returns the trend strength.
+    fn trend_strength(&self) -> f64;
+
+    /// Returns the sum of the seasonal components.
+    fn seasonal_total(&self) -> Vec<T> {
+        let mut total = vec![T::zero(); self.trend().len()];
+        for component in self.seasonal_components() {
+            for (t, &v) in total.iter_mut().zip(component) {
+                *t += v;
+            }
+        }
+        total
+    }
+}
+
+impl Decomposer for StlParams {
+    type Output<T: Float> = StlResult<T>;
+
+    fn decompose<T: Float>(&self, series: &[T], periods: &[usize]) -> Result<StlResult<T>, Error> {
+        self.fit(series, single_period(periods)?)
+    }
+}
+
+impl Decomposer for MstlParams {
+    type Output<T: Float> = MstlResult<T>;
+
+    fn decompose<T: Float>(&self, series: &[T], periods: &[usize]) -> Result<MstlResult<T>, Error> {
+        self.fit(series, periods)
+    }
+}
+
+// the components of a decomposition add up to the series, so only the additive model is supported
+impl Decomposer for ClassicalParams {
+    type Output<T: Float> = StlResult<T>;
+
+    fn decompose<T: Float>(&self, series: &[T], periods: &[usize]) -> Result<StlResult<T>, Error> {
+        if self.model != ClassicalModel::Additive {
+            return Err(Error::InvalidParameter {
+                name: "model",
+                value: ParameterValue::Name("multiplicative"),
+                reason: "must be additive for decompose",
+            });
+        }
+        self.fit(series, single_period(periods)?)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Decomposer for RobustStlParams {
+    type Output<T: Float> = StlResult<T>;
+
+    fn decompose<T: Float>(&self, series: &[T], periods: &[usize]) -> Result<StlResult<T>, Error> {
+        self.fit(series, single_period(periods)?)
+    }
+}
+
+impl Decomposer for StrParams {
+    type Output<T: Float> = StrResult<T>;
+
+    fn decompose<T: Float>(&self, series: &[T], periods: &[usize]) -> Result<StrResult<T>, Error> {
+        let periods = periods.iter().map(|&np| np as f64).collect::<Vec<f64>>();
+        self.fit(series, &periods)
+    }
+}
+
+fn single_period(periods: &[usize]) -> Result<usize, Error> {
+    match periods {
+        [period] => Ok(*period),
+        _ => Err(Error::parameter(
+            "periods",
+            periods.len(),
+            "must have one entry for this method",
+        )),
+    }
+}
+
+impl<T: Float> Decomposition<T> for StlResult<T> {
+    fn seasonal_components(&self) -> Vec<&[T]> {
+        vec![self.seasonal()]
+    }
+
+    fn trend(&self) -> &[T] {
+        self.trend()
+    }
+
+    fn remainder(&self) -> &[T] {
+        self.remainder()
+    }
+
+    fn seasonal_strengths(&self) -> Vec<f64> {
+        vec![self.seasonal_strength()]
+    }
+
+    fn trend_strength(&self) -> f64 {
+        self.trend_strength()
+    }
+}
+
+impl<T: Float> Decomposition<T> for MstlResult<T> {
+    fn seasonal_components(&self) -> Vec<&[T]> {
+        self.seasonal().iter().map(|s| &s[..]).collect()
+    }
+
+    fn trend(&self) -> &[T] {
+        self.trend()
+    }
+
+    fn remainder(&self) -> &[T] {
+        self.remainder()
+    }
+
+    fn seasonal_strengths(&self) -> Vec<f64> {
+        self.seasonal_strength()
+    }
+
+    fn trend_strength(&self) -> f64 {
+        self.trend_strength()
+    }
+}
+
+impl<T: Float> Decomposition<T> for StrResult<T> {
+    fn seasonal_components(&self) -> Vec<&[T]> {
+        self.seasonal().iter().map(|s| &s[..]).collect()
+    }
+
+    fn trend(&self) -> &[T] {
+        self.trend()
+    }
+
+    fn remainder(&self) -> &[T] {
+        self.remainder()
+    }
+
+    fn seasonal_strengths(&self) -> Vec<f64> {
+        self.seasonal_strength()
+    }
+
+    fn trend_strength(&self) -> f64 {
+        self.trend_strength()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stl::test_helpers::*;
+    use crate::{
+        Classical, ClassicalModel, Decomposer, Decomposition, Error, Mstl, ParameterValue, Stl, Str,
+    };
+    use alloc::{vec, vec::Vec};
+
+    fn generate_series() -> Vec<f32> {
+        vec![
+            5.0, 9.0, 2.0, 9.0, 0.0, 6.0, 3.0, 8.0, 5.0, 8.0, 7.0, 8.0, 8.0, 0.0, 2.0, 5.0, 0.0,
+            5.0, 6.0, 7.0, 3.0, 6.0, 1.0, 4.0, 4.0, 4.0, 3.0, 7.0, 5.0, 8.0,
+        ]
+    }
+
+    // seasonally adjusts a series with any method
+    fn adjust<D: Decomposer>(decomposer: &D, series: &[f32], periods: &[usize]) -> Vec<f32> {
+        let result = decomposer.decompose(series, periods).unwrap();
+        series
+            .iter()
+            .zip(result.seasonal_total())
+            .map(|(v, s)| v - s)
+            .collect()
+    }
+
+    #[test]
+    fn test_stl() {
+        let series = generate_series();
+        let result = Stl::params().decompose(&series, &[7]).unwrap();
+        let expected = Stl::fit(&series, 7).unwrap();
+        assert_elements_in_delta(expected.seasonal(), result.seasonal_components()[0]);
+        assert_in_delta(expected.seasonal_strength(), result.seasonal_strengths()[0]);
+
+        let adjusted = adjust(&Stl::params(), &series, &[7]);
+        assert_in_delta(series[0] - expected.seasonal()[0], adjusted[0]);
+    }
+
+    #[test]
+    fn test_mstl() {
+        let series = generate_series();
+        let result = Mstl::params().decompose(&series, &[6, 10]).unwrap();
+        let expected = Mstl::fit(&series, &[6, 10]).unwrap();
+        assert_eq!(2, result.seasonal_components().len());
+        assert_elements_in_delta(&expected.seasonal_strength(), &result.seasonal_strengths());
+        assert_in_delta(
+            expected.trend_strength(),
+            Decomposition::trend_strength(&result),
+        );
+
+        let adjusted = adjust(&Mstl::params(), &series, &[6, 10]);
+        let seasonal = expected.seasonal()[0][0] + expected.seasonal()[1][0];
+        assert_in_delta(series[0] - seasonal, adjusted[0]);
+    }
+
+    #[test]
+    fn test_classical() {
+        let series = generate_series();
+        let result = Classical::params().decompose(&series, &[7]).unwrap();
+        let expected = Classical::fit(&series, 7).unwrap();
+        assert_in_delta(expected.seasonal_strength(), result.seasonal_strengths()[0]);
+        assert!(result.seasonal_strengths()[0] > 0.0);
+        assert!(Decomposition::trend_strength(&result) > 0.0);
+
+        let adjusted = adjust(&Classical::params(), &series, &[7]);
+        assert_in_delta(series[0] - expected.seasonal()[0], adjusted[0]);
+    }
+
+    #[test]
+    fn test_classical_multiplicative() {
+        let result = Classical::params()
+            .model(ClassicalModel::Multiplicative)
+            .decompose(&generate_series(), &[7]);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::InvalidParameter {
+                name: "model",
+                value: ParameterValue::Name("multiplicative"),
+                reason: "must be additive for decompose",
+            }
+        );
+    }
+
+    #[test]
+    fn test_str() {
+        let series = generate_series();
+        let result = Str::params().decompose(&series, &[7]).unwrap();
+        let expected = Str::fit(&series, &[7.0]).unwrap();
+        assert_elements_in_delta(&expected.seasonal()[0], result.seasonal_components()[0]);
+    }
+
+    #[test]
+    fn test_stl_periods() {
+        let result = Stl::params().decompose(&generate_series(), &[6, 10]);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::InvalidParameter {
+                name: "periods",
+                value: ParameterValue::Integer(2),
+                reason: "must have one entry for this method",
+            }
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use alloc::vec::Vec;
+
+use super::Float;
+
+/// A portmanteau test result.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PortmanteauTest {
+    /// The test statistic.
+    pub statistic: f64,
+    /// The p-value from a chi-squared distribution with `lags` degrees of freedom.
+    pub p_value: f64,
+}
+
+/// Summary statistics of the remainder.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RemainderStats {
+    /// The mean.
+    pub mean: f64,
+    /// The sample variance.
+    pub variance: f64,
+    /// The skewness.
+    pub skewness: f64,
+    /// The excess kurtosis.
+    pub kurtosis: f64,
+}
+
+fn mean<T: Float>(series: &[T]) -> f64 {
+    series.iter().map(|v| (*v).as_f64()).sum::<f64>() / series.len() as f64
+}
+
+pub(crate) fn acf<T: Float>(series: &[T], max_lag: usize) -> Vec<f64> {
+    let n = series.len();
+    let mean = mean(series);
+    let diffs = series
+        .iter()
+        .map(|v| (*v).as_f64() - mean)
+        .collect::<Vec<f64>>();
+    let denom = diffs.iter().map(|v| v * v).sum::<f64>();
+
+    (0..=max_lag.min(n.saturating_sub(1)))
+        .map(|k| {
+            diffs
+                .iter()
+                .zip(&diffs[k..])
+                .map(|(a, b)| a * b)
+                .sum::<f64>()
+                / denom
+        })
+        .collect()
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn ljung_box<T: Float>(series: &[T], lags: usize) -> PortmanteauTest {
+    let n = series.len() as f64;
+    let r = acf(series, lags);
+    let statistic = n
+        * (n + 2.0)
+        * r.iter()
+            .enumerate()
+            .skip(1)
+            .map(|(k, rk)| rk * rk / (n - k as f64))
+            .sum::<f64>();
+    PortmanteauTest {
+        statistic,
+        p_value: chi_squared_sf(statistic, r.len() - 1),
+    }
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn box_pierce<T: Float>(series: &[T], lags: usize) -> PortmanteauTest {
+    let n = series.len() as f64;
+    let r = acf(series, lags);
+    let statistic = n * r.iter().skip(1).map(|rk| rk * rk).sum::<f64>();
+    PortmanteauTest {
+        statistic,
+        p_value: chi_squared_sf(statistic, r.len() - 1),
+    }
+}
+
+pub(crate) fn remainder_stats<T: Float>(series: &[T]) -> RemainderStats {
+    let n = series.len() as f64;
+    let mean = mean(series);
+    let mut m2 = 0.0;
+    let mut m3 = 0.0;
+    let mut m4 = 0.0;
+    for v in series {
+        let diff = (*v).as_f64() - mean;
+        let diff2 = diff * diff;
+        m2 += diff2;
+        m3 += diff2 * diff;
+        m4 += diff2 * diff2;
+    }
+    let variance = m2 / (n - 1.0);
+    m2 /= n;
+    m3 /= n;
+    m4 /= n;
+    RemainderStats {
+        mean,
+        variance,
+        skewness: m3 / (m2 * Float::sqrt(&m2)),
+        kurtosis: m4 / (m2 * m2) - 3.0,
+    }
+}
+
+// survival function of the chi-squared distribution
+#[cfg(feature = "std")]
+fn chi_squared_sf(x: f64, df: usize) -> f64 {
+    if df == 0 {
+        return f64::NAN;
+    }
+    if x <= 0.0 {
+        return 1.0;
+    }
+    gamma_q(df as f64 / 2.0, x / 2.0)
+}
+
+// regularized upper incomplete gamma function
+// https://numerical.recipes/book.html (section 6.2)
+#[cfg(feature = "std")]
+fn gamma_q(a: f64, x: f64) -> f64 {
+    const MAX_ITER: usize = 1000;
+    const EPS: f64 = 1e-14;
+    const FPMIN: f64 = 1e-300;
+
+    let gln = ln_gamma(a);
+    let front = (-x + a * x.ln() - gln).exp();
+
+    if x < a + 1.0 {
+        // series representation
+        let mut ap = a;
+        let mut del = 1.0 / a;
+        let mut sum = del;
+        for _ in 0..MAX_ITER {
+            ap += 1.0;
+            del *= x / ap;
+            sum += del;
+            if del.abs() < sum.abs() * EPS {
+                break;
+            }
+        }
+        (1.0 - sum * front).max(0.0)
+    } else {
+        // continued fraction representation
+        let mut b = x + 1.0 - a;
+        let mut c = 1.0 / FPMIN;
+        let mut d = 1.0 / b;
+        let mut h = d;
+        for i in 1..MAX_ITER {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0;
+            d = an * d + b;
+            if d.abs() < FPMIN {
+                d = FPMIN;
+            }
+            c = b + an / c;
+            if c.abs() < FPMIN {
+                c = FPMIN;
+            }
+            d = 1.0 / d;
+            let del = d * c;
+            h *= del;
+            if (del - 1.0).abs() < EPS {
+                break;
+            }
+        }
+        front * h
+    }
+}
+
+// Lanczos approximation
+#[cfg(feature = "std")]
+fn ln_gamma(x: f64) -> f64 {
+    const COF: [f64; 6] = [
+        76.18009172947146,
+        -86.50532032941677,
+        24.01409824083091,
+        -1.231739572450155,
+        0.1208650973866179e-2,
+        -0.5395239384953e-5,
+    ];
+
+    let mut y = x;
+    let mut tmp = x + 5.5;
+    tmp -= (x + 0.5) * tmp.ln();
+    let mut ser = 1.000000000190015;
+    for c in COF {
+        y += 1.0;
+        ser += c / y;
+    }
+    -tmp + (2.5066282746310005 * ser / x).ln()
+}
@@ -3,9 +3,125 @@ use core::fmt;
 
 /// An error.
 #[derive(Debug, Eq, PartialEq)]
+#[non_exhaustive]
 pub enum Error {
+    /// A parameter error with a message (no longer returned by this crate).
     Parameter(&'static str),
+    /// A series error with a message (no longer returned by this crate).
     Series(&'static str),
+    /// A parameter has an invalid value.
+    InvalidParameter {
+        /// The name of the parameter.
+        name: &'static str,
+        /// The offending value.
+        value: ParameterValue,
+        /// The requirement that was not met.
+        reason: &'static str,
+    },
+    /// The series is shorter than required.
+    SeriesTooShort {
+        /// The length of the series.
+        len: usize,
+        /// The minimum length.
+        min_len: usize,
+    },
+    /// The series contains a non-finite value.
+    NonFiniteValue {
+        /// The index of the first non-finite value.
+        index: usize,
+    },
+    /// Two parameters cannot be used together.
+    IncompatibleParameters {
+        /// The name of the parameter.
+        name: &'static str,
+        /// The name of the parameter or method it conflicts with.
+        other: &'static str,
+    },
+    /// The series has no valid values.
+    NoValidValues,
+    /// A column was not found.
+    ColumnNotFound {
+        /// The name of the parameter with the column name.
+        name: &'static str,
+    },
+    /// A column cannot be used.
+    InvalidColumn {
+        /// The name of the parameter with the column or series.
+        name: &'static str,
+        /// The requirement that was not met.
+        reason: &'static str,
+    },
+    /// A parameter must be set.
+    MissingParameter {
+        /// The name of the parameter.
+        name: &'static str,
+    },
+    /// An iterative solver did not converge.
+    NotConverged {
+        /// The number of iterations.
+        iterations: usize,
+    },
+}
+
+/// A parameter value.
+#[derive(Clone, Copy, Debug)]
+pub enum ParameterValue {
+    /// An integer value, like a length or an index (saturated at `i64::MAX`).
+    Integer(i64),
+    /// A floating-point value.
+    Float(f64),
+    /// A named value, like a model.
+    Name(&'static str),
+}
+
+impl PartialEq for ParameterValue {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (ParameterValue::Integer(a), ParameterValue::Integer(b)) => a == b,
+            // compare bits so NaN values are equal to themselves
+            (ParameterValue::Float(a), ParameterValue::Float(b)) => a.to_bits() == b.to_bits(),
+            (ParameterValue::Name(a), ParameterValue::Name(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for ParameterValue {}
+
+impl fmt::Display for ParameterValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParameterValue::Integer(v) => write!(f, "{}", v),
+            ParameterValue::Float(v) => write!(f, "{}", v),
+            ParameterValue::Name(v) => f.write_str(v),
+        }
+    }
+}
+
+impl Error {
+    pub(crate) fn parameter(name: &'static str, value: usize, reason: &'static str) -> Self {
+        Error::InvalidParameter {
+            name,
+            value: ParameterValue::Integer(i64::try_from(value).unwrap_or(i64::MAX)),
+            reason,
+        }
+    }
+
+    pub(crate) fn degree(name: &'static str, value: i32) -> Self {
+        Error::InvalidParameter {
+            name,
+            value: ParameterValue::Integer(value.into()),
+            reason: "must be 0 or 1",
+        }
+    }
+
+    pub(crate) fn float_parameter(name: &'static str, value: f64, reason: &'static str) -> Self {
+        Error::InvalidParameter {
+            name,
+            value: ParameterValue::Float(value),
+            reason,
+        }
+    }
 }
 
 impl error::Error for Error {}
@@ -15,6 +131,29 @@ impl fmt::Display for Error {
         match *self {
             Error::Parameter(err) => f.write_str(err),
             Error::Series(err) => f.write_str(err),
+            Error::InvalidParameter {
+                name,
+                value,
+                reason,
+            } => write!(f, "{} {}, got {}", name, reason, value),
+            Error::SeriesTooShort { len, min_len } => write!(
+                f,
+                "series must have at least {} values, got {}",
+                min_len, len
+            ),
+            Error::NonFiniteValue { index } => {
+                write!(f, "series has a non-finite value at index {}", index)
+            }
+            Error::IncompatibleParameters { name, other } => {
+                write!(f, "{} cannot be used with {}", name, other)
+            }
+            Error::NoValidValues => f.write_str("series has no valid values"),
+            Error::ColumnNotFound { name } => write!(f, "{} not found", name),
+            Error::InvalidColumn { name, reason } => write!(f, "{} {}", name, reason),
+            Error::MissingParameter { name } => write!(f, "{} must be set", name),
+            Error::NotConverged { iterations } => {
+                write!(f, "solver did not converge in {} iterations", iterations)
+            }
         }
     }
 }
@@ -0,0 +1,254 @@
+// Extends the series with forecasts and backcasts before decomposing, similar to
+// X-13ARIMA-SEATS, so estimates near the ends are revised less as data arrives.
+// Forecasts come from a preliminary decomposition: the trend is extrapolated with
+// the drift over the last cycle and the seasonal component repeats the projected cycle.
+
+use alloc::vec::Vec;
+
+use super::{Error, Float, MstlParams, MstlResult, StlParams, StlResult};
+
+impl StlParams {
+    pub(crate) fn fit_extended<T: Float>(
+        &self,
+        series: &[T],
+        period: usize,
+    ) -> Result<(StlResult<T>, Vec<T>), Error> {
+        let h = self.extension;
+        let mut params = self.clone();
+        params.extension(0);
+
+        let (forecast, backcast) = forecasts(series, |s| {
+            let (result, projected) = params.fit_projected(s, period)?;
+            Ok(forecast(result.trend(), period, h, |i| {
+                projected[i % period]
+            }))
+        })?;
+        let extended = extend(series, &forecast, &backcast);
+        let (result, projected) = params.fit_projected(&extended, period)?;
+
+        let n = series.len();
+        let projected = project(&result.seasonal, &projected, h + n);
+        let result = StlResult {
+            seasonal: trim(&result.seasonal, h, n),
+            trend: trim(&result.trend, h, n),
+            remainder: trim(&result.remainder, h, n),
+            weights: trim(&result.weights, h, n),
+            period,
+        };
+        Ok((result, projected))
+    }
+}
+
+impl MstlParams {
+    pub(crate) fn fit_extended<T: Float>(
+        &self,
+        series: &[T],
+        periods: &[usize],
+    ) -> Result<(MstlResult<T>, Vec<Vec<T>>), Error> {
+        if self.lambda.is_some() {
+            return Err(Error::IncompatibleParameters {
+                name: "lambda",
+                other: "extension",
+            });
+        }
+        if self.regressors.is_some() {
+            return Err(Error::IncompatibleParameters {
+                name: "regressors",
+                other: "extension",
+            });
+        }
+
+        let h = self.extension;
+        let mut params = self.clone();
+        params.extension(0);
+
+        let (forecast, backcast) = forecasts(series, |s| {
+            let (result, projected) = params.fit_projected(s, periods)?;
+            let window = periods.iter().copied().max().unwrap_or(2);
+            Ok(forecast(result.trend(), window, h, |i| {
+                periods
+                    .iter()
+                    .zip(&projected)
+                    .map(|(&np, p)| p[i % np])
+                    .sum()
+            }))
+        })?;
+        let extended = extend(series, &forecast, &backcast);
+        let (result, projected) = params.fit_projected(&extended, periods)?;
+
+        let n = series.len();
+        let projected = result
+            .seasonal
+            .iter()
+            .zip(&projected)
+            .map(|(s, p)| project(s, p, h + n))
+            .collect();
+        let result = MstlResult {
+            seasonal: result.seasonal.iter().map(|s| trim(s, h, n)).collect(),
+            trend: trim(&result.trend, h, n),
+            remainder: trim(&result.remainder, h, n),
+            periods: periods.to_vec(),
+            regression: None,
+            coefficients: None,
+        };
+        Ok((result, projected))
+    }
+}
+
+// returns forecasts past the end and backcasts before the start, in time order
+fn forecasts<T: Float, F>(series: &[T], fit: F) -> Result<(Vec<T>, Vec<T>), Error>
+where
+    F: Fn(&[T]) -> Result<Vec<T>, Error>,
+{
+    let forecast = fit(series)?;
+    let reversed = series.iter().rev().copied().collect::<Vec<T>>();
+    let mut backcast = fit(&reversed)?;
+    backcast.reverse();
+    Ok((forecast, backcast))
+}
+
+fn forecast<T: Float, F: Fn(usize) -> T>(
+    trend: &[T],
+    window: usize,
+    h: usize,
+    seasonal: F,
+) -> Vec<T> {
+    let n = trend.len();
+    let last = trend[n - 1];
+    let drift = (last - trend[n - 1 - window]) / T::from_usize(window);
+    (0..h)
+        .map(|i| last + drift * T::from_usize(i + 1) + seasonal(i))
+        .collect()
+}
+
+fn extend<T: Float>(series: &[T], forecast: &[T], backcast: &[T]) -> Vec<T> {
+    let mut extended = Vec::with_capacity(series.len() + forecast.len() + backcast.len());
+    extended.extend_from_slice(backcast);
+    extended.extend_from_slice(series);
+    extended.extend_from_slice(forecast);
+    extended
+}
+
+fn trim<T: Float>(component: &[T], h: usize, n: usize) -> Vec<T> {
+    component[h..h + n].to_vec()
+}
+
+// the cycle after position `end`, using the extended fit where available
+fn project<T: Float>(seasonal: &[T], projected: &[T], end: usize) -> Vec<T> {
+    let np = projected.len();
+    (end..end + np)
+        .map(|p| {
+            if p < seasonal.len() {
+                seasonal[p]
+            } else {
+                projected[(p - seasonal.len()) % np]
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stl::test_helpers::*;
+    use crate::{Error, Mstl, Stl};
+    use alloc::{vec, vec::Vec};
+
+    fn generate_series() -> Vec<f32> {
+        vec![
+            5.0, 9.0, 2.0, 9.0, 0.0, 6.0, 3.0, 8.0, 5.0, 8.0, 7.0, 8.0, 8.0, 0.0, 2.0, 5.0, 0.0,
+            5.0, 6.0, 7.0, 3.0, 6.0, 1.0, 4.0, 4.0, 4.0, 3.0, 7.0, 5.0, 8.0,
+        ]
+    }
+
+    // a seasonal pattern with a trend and noise
+    fn generate_long_series() -> Vec<f32> {
+        let pattern = [1.0, 3.0, -2.0, 0.5, 2.0, -1.5, -3.0];
+        let noise = [0.3, -0.2, 0.1, -0.4, 0.2, 0.0, -0.1, 0.4, -0.3, 0.1, 0.2];
+        (0..140)
+            .map(|i| 10.0 + 0.05 * i as f32 + pattern[i % 7] + noise[i % 11])
+            .collect()
+    }
+
+    #[test]
+    fn test_stl() {
+        let series = generate_series();
+        let result = Stl::params().extension(7).fit(&series, 7).unwrap();
+        assert_eq!(30, result.seasonal().len());
+        assert_eq!(30, result.weights().len());
+        for (i, &v) in series.iter().enumerate() {
+            assert_in_delta(
+                v,
+                result.seasonal()[i] + result.trend()[i] + result.remainder()[i],
+            );
+        }
+    }
+
+    #[test]
+    fn test_stl_revisions() {
+        // compare the trend at the end of a prefix with the trend for the full series
+        let series = generate_long_series();
+        let revision = |extension| {
+            let mut params = Stl::params();
+            params.extension(extension);
+            let full = params.fit(&series, 7).unwrap();
+            (105..133)
+                .map(|end| {
+                    let prefix = params.fit(&series[..end], 7).unwrap();
+                    (prefix.trend()[end - 1] - full.trend()[end - 1]).abs()
+                })
+                .sum::<f32>()
+        };
+        assert!(revision(14) < revision(0));
+    }
+
+    #[test]
+    fn test_stl_model() {
+        let series = generate_long_series();
+        let model = Stl::params().extension(3).fit_model(&series, 7).unwrap();
+        assert_eq!(7, model.projected_seasonal().len());
+        assert_eq!(140, model.result().seasonal().len());
+    }
+
+    #[test]
+    fn test_mstl() {
+        let series = generate_series();
+        let result = Mstl::params().extension(10).fit(&series, &[6, 10]).unwrap();
+        assert_eq!(2, result.seasonal().len());
+        assert_eq!(30, result.seasonal()[1].len());
+        for (i, &v) in series.iter().enumerate() {
+            let seasonal = result.seasonal()[0][i] + result.seasonal()[1][i];
+            assert_in_delta(v, seasonal + result.trend()[i] + result.remainder()[i]);
+        }
+    }
+
+    #[test]
+    fn test_mstl_lambda() {
+        let result = Mstl::params()
+            .lambda(0.5)
+            .extension(10)
+            .fit(&generate_series(), &[6, 10]);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::IncompatibleParameters {
+                name: "lambda",
+                other: "extension",
+            }
+        );
+    }
+
+    #[test]
+    fn test_mstl_inner_extension() {
+        let mut stl_params = Stl::params();
+        stl_params.extension(10);
+        let result = Mstl::params()
+            .stl_params(stl_params)
+            .fit(&generate_series(), &[6, 10]);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::IncompatibleParameters {
+                name: "extension",
+                other: "stl_params",
+            }
+        );
+    }
+}
@@ -0,0 +1,133 @@
+// Hyndman, R. J., Kang, Y., Montero-Manso, P., Talagala, T., Wang, E., Yang, Y., & O'Hara-Wild, M. (2023).
+// tsfeatures: Time Series Feature Extraction. https://pkg.robjhyndman.com/tsfeatures/
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use super::diagnostics::acf;
+use super::stl_result::var;
+use super::Float;
+
+/// STL-based features.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StlFeatures {
+    /// The trend strength.
+    pub trend_strength: f64,
+    /// The seasonal strength.
+    pub seasonal_strength: f64,
+    /// The variance of the leave-one-out variances of the remainder.
+    pub spikiness: f64,
+    /// The linear coefficient of an orthogonal quadratic regression on the trend.
+    pub linearity: f64,
+    /// The quadratic coefficient of an orthogonal quadratic regression on the trend.
+    pub curvature: f64,
+    /// The phase of the largest seasonal value.
+    pub seasonal_peak: usize,
+    /// The phase of the smallest seasonal value.
+    pub seasonal_trough: usize,
+    /// The first autocorrelation coefficient of the remainder.
+    pub remainder_acf1: f64,
+    /// The sum of squares of the first ten autocorrelation coefficients of the remainder.
+    pub remainder_acf10: f64,
+}
+
+/// MSTL-based features.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MstlFeatures {
+    /// The trend strength.
+    pub trend_strength: f64,
+    /// The seasonal strength of each seasonal component.
+    pub seasonal_strength: Vec<f64>,
+    /// The variance of the leave-one-out variances of the remainder.
+    pub spikiness: f64,
+    /// The linear coefficient of an orthogonal quadratic regression on the trend.
+    pub linearity: f64,
+    /// The quadratic coefficient of an orthogonal quadratic regression on the trend.
+    pub curvature: f64,
+    /// The phase of the largest value of each seasonal component.
+    pub seasonal_peak: Vec<usize>,
+    /// The phase of the smallest value of each seasonal component.
+    pub seasonal_trough: Vec<usize>,
+    /// The first autocorrelation coefficient of the remainder.
+    pub remainder_acf1: f64,
+    /// The sum of squares of the first ten autocorrelation coefficients of the remainder.
+    pub remainder_acf10: f64,
+}
+
+// indices from the first to the last point where the trend and remainder are not NaN
+pub(crate) fn finite_span<T: Float>(trend: &[T], remainder: &[T]) -> Range<usize> {
+    let valid = |i: &usize| !trend[*i].as_f64().is_nan() && !remainder[*i].as_f64().is_nan();
+    let n = trend.len().min(remainder.len());
+    let start = (0..n).find(valid).unwrap_or(n);
+    let end = (start..n).rev().find(valid).map_or(start, |i| i + 1);
+    start..end
+}
+
+pub(crate) fn spikiness<T: Float>(remainder: &[T]) -> f64 {
+    let n = remainder.len() as f64;
+    let mean = remainder.iter().map(|v| (*v).as_f64()).sum::<f64>() / n;
+    let var_e = var(remainder);
+    let varloo = remainder
+        .iter()
+        .map(|v| {
+            let diff = (*v).as_f64() - mean;
+            (var_e * (n - 1.0) - diff * diff) / (n - 2.0)
+        })
+        .collect::<Vec<f64>>();
+    var(&varloo)
+}
+
+// coefficients of the trend on orthonormal linear and quadratic polynomials (like R's poly)
+pub(crate) fn linearity_curvature<T: Float>(trend: &[T]) -> (f64, f64) {
+    let n = trend.len();
+    let center = (n as f64 - 1.0) / 2.0;
+
+    let mut p1 = (0..n).map(|i| i as f64 - center).collect::<Vec<f64>>();
+    normalize(&mut p1);
+
+    let mut p2 = p1.iter().map(|v| v * v).collect::<Vec<f64>>();
+    let mean = p2.iter().sum::<f64>() / n as f64;
+    for v in p2.iter_mut() {
+        *v -= mean;
+    }
+    let proj = dot(&p2, &p1);
+    for (v, u) in p2.iter_mut().zip(&p1) {
+        *v -= proj * u;
+    }
+    normalize(&mut p2);
+
+    let trend = trend.iter().map(|v| (*v).as_f64()).collect::<Vec<f64>>();
+    (dot(&p1, &trend), dot(&p2, &trend))
+}
+
+pub(crate) fn peak_trough<T: Float>(seasonal: &[T], period: usize) -> (usize, usize) {
+    let mut peak = 0;
+    let mut trough = 0;
+    for (i, v) in seasonal.iter().enumerate() {
+        if *v > seasonal[peak] {
+            peak = i;
+        }
+        if *v < seasonal[trough] {
+            trough = i;
+        }
+    }
+    (peak % period, trough % period)
+}
+
+pub(crate) fn remainder_acf<T: Float>(remainder: &[T]) -> (f64, f64) {
+    let r = acf(remainder, 10);
+    let acf1 = r.get(1).copied().unwrap_or(f64::NAN);
+    let acf10 = r.iter().skip(1).map(|v| v * v).sum();
+    (acf1, acf10)
+}
+
+fn dot(x: &[f64], y: &[f64]) -> f64 {
+    x.iter().zip(y).map(|(a, b)| a * b).sum()
+}
+
+fn normalize(x: &mut [f64]) {
+    let norm = Float::sqrt(&dot(x, x));
+    for v in x.iter_mut() {
+        *v /= norm;
+    }
+}
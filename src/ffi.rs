@@ -0,0 +1,688 @@
+//! C API.
+//!
+//! Functions return `STLRS_OK` on success or an error code on failure.
+//! The message for the last error on the current thread is available
+//! from `stlrs_last_error_message`.
+
+#![allow(clippy::too_many_arguments)]
+#![allow(non_camel_case_types)]
+
+use std::cell::RefCell;
+use std::ffi::{c_char, c_int, CString};
+use std::ptr;
+use std::slice;
+
+use super::{Error, MstlParams, StlParams};
+
+/// Success.
+pub const STLRS_OK: c_int = 0;
+/// Invalid parameter.
+pub const STLRS_ERROR_PARAMETER: c_int = 1;
+/// Invalid series.
+pub const STLRS_ERROR_SERIES: c_int = 2;
+/// Null pointer passed for a required argument.
+pub const STLRS_ERROR_NULL_POINTER: c_int = 3;
+
+/// An opaque set of STL parameters.
+pub struct stlrs_stl_params(StlParams);
+
+/// An opaque set of MSTL parameters.
+pub struct stlrs_mstl_params(MstlParams);
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: &str) {
+    LAST_ERROR.with(|e| *e.borrow_mut() = CString::new(message).ok());
+}
+
+fn error_code(err: Error) -> c_int {
+    set_last_error(&err.to_string());
+    match err {
+        Error::Parameter(_)
+        | Error::InvalidParameter { .. }
+        | Error::IncompatibleParameters { .. }
+        | Error::MissingParameter { .. }
+        | Error::ColumnNotFound { .. }
+        | Error::InvalidColumn { .. } => STLRS_ERROR_PARAMETER,
+        Error::Series(_)
+        | Error::SeriesTooShort { .. }
+        | Error::NonFiniteValue { .. }
+        | Error::NoValidValues
+        | Error::NotConverged { .. } => STLRS_ERROR_SERIES,
+    }
+}
+
+fn null_pointer() -> c_int {
+    set_last_error("null pointer");
+    STLRS_ERROR_NULL_POINTER
+}
+
+unsafe fn copy_to(values: &[f64], out: *mut f64) {
+    if !out.is_null() {
+        ptr::copy_nonoverlapping(values.as_ptr(), out, values.len());
+    }
+}
+
+/// Returns the message for the last error on the current thread, or null if there is none.
+///
+/// The pointer is valid until the next call that fails on the same thread.
+#[no_mangle]
+pub extern "C" fn stlrs_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|e| match &*e.borrow() {
+        Some(message) => message.as_ptr(),
+        None => ptr::null(),
+    })
+}
+
+/// Creates a new set of STL parameters.
+#[no_mangle]
+pub extern "C" fn stlrs_stl_params_new() -> *mut stlrs_stl_params {
+    Box::into_raw(Box::new(stlrs_stl_params(StlParams::new())))
+}
+
+/// Frees a set of STL parameters.
+///
+/// # Safety
+///
+/// `params` must be null or returned by `stlrs_stl_params_new` and not already freed.
+#[no_mangle]
+pub unsafe extern "C" fn stlrs_stl_params_free(params: *mut stlrs_stl_params) {
+    if !params.is_null() {
+        drop(Box::from_raw(params));
+    }
+}
+
+/// Sets the length of the seasonal smoother.
+///
+/// # Safety
+///
+/// `params` must be a valid pointer returned by `stlrs_stl_params_new`.
+#[no_mangle]
+pub unsafe extern "C" fn stlrs_stl_params_seasonal_length(
+    params: *mut stlrs_stl_params,
+    length: usize,
+) -> c_int {
+    match params.as_mut() {
+        Some(params) => {
+            params.0.seasonal_length(length);
+            STLRS_OK
+        }
+        None => null_pointer(),
+    }
+}
+
+/// Sets the length of the trend smoother.
+///
+/// # Safety
+///
+/// `params` must be a valid pointer returned by `stlrs_stl_params_new`.
+#[no_mangle]
+pub unsafe extern "C" fn stlrs_stl_params_trend_length(
+    params: *mut stlrs_stl_params,
+    length: usize,
+) -> c_int {
+    match params.as_mut() {
+        Some(params) => {
+            params.0.trend_length(length);
+            STLRS_OK
+        }
+        None => null_pointer(),
+    }
+}
+
+/// Sets the length of the low-pass filter.
+///
+/// # Safety
+///
+/// `params` must be a valid pointer returned by `stlrs_stl_params_new`.
+#[no_mangle]
+pub unsafe extern "C" fn stlrs_stl_params_low_pass_length(
+    params: *mut stlrs_stl_params,
+    length: usize,
+) -> c_int {
+    match params.as_mut() {
+        Some(params) => {
+            params.0.low_pass_length(length);
+            STLRS_OK
+        }
+        None => null_pointer(),
+    }
+}
+
+/// Sets the degree of locally-fitted polynomial in seasonal smoothing.
+///
+/// # Safety
+///
+/// `params` must be a valid pointer returned by `stlrs_stl_params_new`.
+#[no_mangle]
+pub unsafe extern "C" fn stlrs_stl_params_seasonal_degree(
+    params: *mut stlrs_stl_params,
+    degree: i32,
+) -> c_int {
+    match params.as_mut() {
+        Some(params) => {
+            params.0.seasonal_degree(degree);
+            STLRS_OK
+        }
+        None => null_pointer(),
+    }
+}
+
+/// Sets the degree of locally-fitted polynomial in trend smoothing.
+///
+/// # Safety
+///
+/// `params` must be a valid pointer returned by `stlrs_stl_params_new`.
+#[no_mangle]
+pub unsafe extern "C" fn stlrs_stl_params_trend_degree(
+    params: *mut stlrs_stl_params,
+    degree: i32,
+) -> c_int {
+    match params.as_mut() {
+        Some(params) => {
+            params.0.trend_degree(degree);
+            STLRS_OK
+        }
+        None => null_pointer(),
+    }
+}
+
+/// Sets the degree of locally-fitted polynomial in low-pass smoothing.
+///
+/// # Safety
+///
+/// `params` must be a valid pointer returned by `stlrs_stl_params_new`.
+#[no_mangle]
+pub unsafe extern "C" fn stlrs_stl_params_low_pass_degree(
+    params: *mut stlrs_stl_params,
+    degree: i32,
+) -> c_int {
+    match params.as_mut() {
+        Some(params) => {
+            params.0.low_pass_degree(degree);
+            STLRS_OK
+        }
+        None => null_pointer(),
+    }
+}
+
+/// Sets the skipping value for seasonal smoothing.
+///
+/// # Safety
+///
+/// `params` must be a valid pointer returned by `stlrs_stl_params_new`.
+#[no_mangle]
+pub unsafe extern "C" fn stlrs_stl_params_seasonal_jump(
+    params: *mut stlrs_stl_params,
+    jump: usize,
+) -> c_int {
+    match params.as_mut() {
+        Some(params) => {
+            params.0.seasonal_jump(jump);
+            STLRS_OK
+        }
+        None => null_pointer(),
+    }
+}
+
+/// Sets the skipping value for trend smoothing.
+///
+/// # Safety
+///
+/// `params` must be a valid pointer returned by `stlrs_stl_params_new`.
+#[no_mangle]
+pub unsafe extern "C" fn stlrs_stl_params_trend_jump(
+    params: *mut stlrs_stl_params,
+    jump: usize,
+) -> c_int {
+    match params.as_mut() {
+        Some(params) => {
+            params.0.trend_jump(jump);
+            STLRS_OK
+        }
+        None => null_pointer(),
+    }
+}
+
+/// Sets the skipping value for low-pass smoothing.
+///
+/// # Safety
+///
+/// `params` must be a valid pointer returned by `stlrs_stl_params_new`.
+#[no_mangle]
+pub unsafe extern "C" fn stlrs_stl_params_low_pass_jump(
+    params: *mut stlrs_stl_params,
+    jump: usize,
+) -> c_int {
+    match params.as_mut() {
+        Some(params) => {
+            params.0.low_pass_jump(jump);
+            STLRS_OK
+        }
+        None => null_pointer(),
+    }
+}
+
+/// Sets the number of loops for updating the seasonal and trend components.
+///
+/// # Safety
+///
+/// `params` must be a valid pointer returned by `stlrs_stl_params_new`.
+#[no_mangle]
+pub unsafe extern "C" fn stlrs_stl_params_inner_loops(
+    params: *mut stlrs_stl_params,
+    loops: usize,
+) -> c_int {
+    match params.as_mut() {
+        Some(params) => {
+            params.0.inner_loops(loops);
+            STLRS_OK
+        }
+        None => null_pointer(),
+    }
+}
+
+/// Sets the number of iterations of robust fitting.
+///
+/// # Safety
+///
+/// `params` must be a valid pointer returned by `stlrs_stl_params_new`.
+#[no_mangle]
+pub unsafe extern "C" fn stlrs_stl_params_outer_loops(
+    params: *mut stlrs_stl_params,
+    loops: usize,
+) -> c_int {
+    match params.as_mut() {
+        Some(params) => {
+            params.0.outer_loops(loops);
+            STLRS_OK
+        }
+        None => null_pointer(),
+    }
+}
+
+/// Sets whether robustness iterations are to be used.
+///
+/// # Safety
+///
+/// `params` must be a valid pointer returned by `stlrs_stl_params_new`.
+#[no_mangle]
+pub unsafe extern "C" fn stlrs_stl_params_robust(
+    params: *mut stlrs_stl_params,
+    robust: bool,
+) -> c_int {
+    match params.as_mut() {
+        Some(params) => {
+            params.0.robust(robust);
+            STLRS_OK
+        }
+        None => null_pointer(),
+    }
+}
+
+/// Sets whether to check the series for non-finite values (enabled by default).
+///
+/// # Safety
+///
+/// `params` must be a valid pointer returned by `stlrs_stl_params_new`.
+#[no_mangle]
+pub unsafe extern "C" fn stlrs_stl_params_check_finite(
+    params: *mut stlrs_stl_params,
+    check_finite: bool,
+) -> c_int {
+    match params.as_mut() {
+        Some(params) => {
+            params.0.check_finite(check_finite);
+            STLRS_OK
+        }
+        None => null_pointer(),
+    }
+}
+
+/// Decomposes a time series.
+///
+/// Each output buffer must have room for `n` values and may be null to skip that component.
+///
+/// # Safety
+///
+/// `params` must be a valid pointer returned by `stlrs_stl_params_new`,
+/// `series` must point to `n` values, and non-null outputs must point to `n` writable values.
+#[no_mangle]
+pub unsafe extern "C" fn stlrs_stl_fit(
+    params: *const stlrs_stl_params,
+    series: *const f64,
+    n: usize,
+    period: usize,
+    seasonal: *mut f64,
+    trend: *mut f64,
+    remainder: *mut f64,
+    weights: *mut f64,
+) -> c_int {
+    let Some(params) = params.as_ref() else {
+        return null_pointer();
+    };
+    if series.is_null() {
+        return null_pointer();
+    }
+    let series = slice::from_raw_parts(series, n);
+
+    match params.0.fit(series, period) {
+        Ok(result) => {
+            copy_to(result.seasonal(), seasonal);
+            copy_to(result.trend(), trend);
+            copy_to(result.remainder(), remainder);
+            copy_to(result.weights(), weights);
+            STLRS_OK
+        }
+        Err(err) => error_code(err),
+    }
+}
+
+/// Creates a new set of MSTL parameters.
+#[no_mangle]
+pub extern "C" fn stlrs_mstl_params_new() -> *mut stlrs_mstl_params {
+    Box::into_raw(Box::new(stlrs_mstl_params(MstlParams::new())))
+}
+
+/// Frees a set of MSTL parameters.
+///
+/// # Safety
+///
+/// `params` must be null or returned by `stlrs_mstl_params_new` and not already freed.
+#[no_mangle]
+pub unsafe extern "C" fn stlrs_mstl_params_free(params: *mut stlrs_mstl_params) {
+    if !params.is_null() {
+        drop(Box::from_raw(params));
+    }
+}
+
+/// Sets the number of iterations.
+///
+/// # Safety
+///
+/// `params` must be a valid pointer returned by `stlrs_mstl_params_new`.
+#[no_mangle]
+pub unsafe extern "C" fn stlrs_mstl_params_iterations(
+    params: *mut stlrs_mstl_params,
+    iterations: usize,
+) -> c_int {
+    match params.as_mut() {
+        Some(params) => {
+            params.0.iterations(iterations);
+            STLRS_OK
+        }
+        None => null_pointer(),
+    }
+}
+
+/// Sets lambda for Box-Cox transformation.
+///
+/// # Safety
+///
+/// `params` must be a valid pointer returned by `stlrs_mstl_params_new`.
+#[no_mangle]
+pub unsafe extern "C" fn stlrs_mstl_params_lambda(
+    params: *mut stlrs_mstl_params,
+    lambda: f32,
+) -> c_int {
+    match params.as_mut() {
+        Some(params) => {
+            params.0.lambda(lambda);
+            STLRS_OK
+        }
+        None => null_pointer(),
+    }
+}
+
+/// Sets whether to check the series for non-finite values (enabled by default).
+///
+/// # Safety
+///
+/// `params` must be a valid pointer returned by `stlrs_mstl_params_new`.
+#[no_mangle]
+pub unsafe extern "C" fn stlrs_mstl_params_check_finite(
+    params: *mut stlrs_mstl_params,
+    check_finite: bool,
+) -> c_int {
+    match params.as_mut() {
+        Some(params) => {
+            params.0.check_finite(check_finite);
+            STLRS_OK
+        }
+        None => null_pointer(),
+    }
+}
+
+/// Sets the lengths of the seasonal smoothers.
+///
+/// # Safety
+///
+/// `params` must be a valid pointer returned by `stlrs_mstl_params_new`
+/// and `lengths` must point to `len` values.
+#[no_mangle]
+pub unsafe extern "C" fn stlrs_mstl_params_seasonal_lengths(
+    params: *mut stlrs_mstl_params,
+    lengths: *const usize,
+    len: usize,
+) -> c_int {
+    let Some(params) = params.as_mut() else {
+        return null_pointer();
+    };
+    if lengths.is_null() {
+        return null_pointer();
+    }
+    params
+        .0
+        .seasonal_lengths(slice::from_raw_parts(lengths, len));
+    STLRS_OK
+}
+
+/// Sets the STL parameters, which are copied.
+///
+/// # Safety
+///
+/// `params` must be a valid pointer returned by `stlrs_mstl_params_new`
+/// and `stl_params` must be a valid pointer returned by `stlrs_stl_params_new`.
+#[no_mangle]
+pub unsafe extern "C" fn stlrs_mstl_params_stl_params(
+    params: *mut stlrs_mstl_params,
+    stl_params: *const stlrs_stl_params,
+) -> c_int {
+    match (params.as_mut(), stl_params.as_ref()) {
+        (Some(params), Some(stl_params)) => {
+            params.0.stl_params(stl_params.0.clone());
+            STLRS_OK
+        }
+        _ => null_pointer(),
+    }
+}
+
+/// Decomposes a time series with multiple seasonal periods.
+///
+/// `seasonal` must have room for `periods_len * n` values and is filled with one
+/// component after another in the order of `periods`. `trend` and `remainder` must
+/// have room for `n` values. Any output may be null to skip that component.
+///
+/// # Safety
+///
+/// `params` must be a valid pointer returned by `stlrs_mstl_params_new`,
+/// `series` must point to `n` values, `periods` must point to `periods_len` values,
+/// and non-null outputs must point to enough writable values.
+#[no_mangle]
+pub unsafe extern "C" fn stlrs_mstl_fit(
+    params: *const stlrs_mstl_params,
+    series: *const f64,
+    n: usize,
+    periods: *const usize,
+    periods_len: usize,
+    seasonal: *mut f64,
+    trend: *mut f64,
+    remainder: *mut f64,
+) -> c_int {
+    let Some(params) = params.as_ref() else {
+        return null_pointer();
+    };
+    if series.is_null() || periods.is_null() {
+        return null_pointer();
+    }
+    let series = slice::from_raw_parts(series, n);
+    let periods = slice::from_raw_parts(periods, periods_len);
+
+    match params.0.fit(series, periods) {
+        Ok(result) => {
+            if !seasonal.is_null() {
+                for (i, s) in result.seasonal().iter().enumerate() {
+                    copy_to(s, seasonal.add(i * n));
+                }
+            }
+            copy_to(result.trend(), trend);
+            copy_to(result.remainder(), remainder);
+            STLRS_OK
+        }
+        Err(err) => error_code(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stl::test_helpers::*;
+    use std::ffi::CStr;
+
+    fn generate_series() -> Vec<f64> {
+        vec![
+            5.0, 9.0, 2.0, 9.0, 0.0, 6.0, 3.0, 8.0, 5.0, 8.0, 7.0, 8.0, 8.0, 0.0, 2.0, 5.0, 0.0,
+            5.0, 6.0, 7.0, 3.0, 6.0, 1.0, 4.0, 4.0, 4.0, 3.0, 7.0, 5.0, 8.0,
+        ]
+    }
+
+    #[test]
+    fn test_stl() {
+        let series = generate_series();
+        let mut seasonal = vec![0.0; 30];
+        let mut trend = vec![0.0; 30];
+        let mut weights = vec![0.0; 30];
+        unsafe {
+            let params = stlrs_stl_params_new();
+            assert_eq!(STLRS_OK, stlrs_stl_params_robust(params, true));
+            let code = stlrs_stl_fit(
+                params,
+                series.as_ptr(),
+                series.len(),
+                7,
+                seasonal.as_mut_ptr(),
+                trend.as_mut_ptr(),
+                ptr::null_mut(),
+                weights.as_mut_ptr(),
+            );
+            stlrs_stl_params_free(params);
+            assert_eq!(STLRS_OK, code);
+        }
+        assert_elements_in_delta(
+            &[0.14922355, 0.47939026, -1.833231, 1.7411387, 0.8200711],
+            &seasonal[..5],
+        );
+        assert_elements_in_delta(
+            &[5.397365, 5.4745436, 5.5517216, 5.6499176, 5.748114],
+            &trend[..5],
+        );
+        assert_elements_in_delta(
+            &[0.99374926, 0.8129377, 0.9385952, 0.9458036, 0.29742217],
+            &weights[..5],
+        );
+    }
+
+    #[test]
+    fn test_stl_error() {
+        let series = generate_series();
+        unsafe {
+            let params = stlrs_stl_params_new();
+            let code = stlrs_stl_fit(
+                params,
+                series.as_ptr(),
+                series.len(),
+                16,
+                ptr::null_mut(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+            );
+            stlrs_stl_params_free(params);
+            assert_eq!(STLRS_ERROR_SERIES, code);
+            let message = CStr::from_ptr(stlrs_last_error_message());
+            assert_eq!(
+                "series must have at least 32 values, got 30",
+                message.to_str().unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_null_pointer() {
+        let code = unsafe { stlrs_stl_params_robust(ptr::null_mut(), true) };
+        assert_eq!(STLRS_ERROR_NULL_POINTER, code);
+    }
+
+    #[test]
+    fn test_mstl() {
+        let series = generate_series();
+        let periods = [6, 10];
+        let mut seasonal = vec![0.0; 60];
+        let mut trend = vec![0.0; 30];
+        let mut remainder = vec![0.0; 30];
+        unsafe {
+            let params = stlrs_mstl_params_new();
+            let code = stlrs_mstl_fit(
+                params,
+                series.as_ptr(),
+                series.len(),
+                periods.as_ptr(),
+                periods.len(),
+                seasonal.as_mut_ptr(),
+                trend.as_mut_ptr(),
+                remainder.as_mut_ptr(),
+            );
+            stlrs_mstl_params_free(params);
+            assert_eq!(STLRS_OK, code);
+        }
+        assert_elements_in_delta(
+            &[0.28318232, 0.70529824, -1.980384, 2.1643379, -2.3356874],
+            &seasonal[..5],
+        );
+        assert_elements_in_delta(
+            &[1.4130436, 1.6048906, 0.050958008, -1.8706754, -1.7704514],
+            &seasonal[30..35],
+        );
+        assert_elements_in_delta(
+            &[5.139485, 5.223691, 5.3078976, 5.387292, 5.4666862],
+            &trend[..5],
+        );
+        assert_elements_in_delta(
+            &[-1.835711, 1.4661198, -1.3784716, 3.319045, -1.3605475],
+            &remainder[..5],
+        );
+    }
+
+    #[test]
+    fn test_mstl_error() {
+        let series = generate_series();
+        unsafe {
+            let params = stlrs_mstl_params_new();
+            assert_eq!(STLRS_OK, stlrs_mstl_params_lambda(params, 2.0));
+            let code = stlrs_mstl_fit(
+                params,
+                series.as_ptr(),
+                series.len(),
+                [7].as_ptr(),
+                1,
+                ptr::null_mut(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+            );
+            stlrs_mstl_params_free(params);
+            assert_eq!(STLRS_ERROR_PARAMETER, code);
+        }
+    }
+}
@@ -23,10 +23,129 @@ pub trait Float:
 
     fn abs(&self) -> Self;
     fn as_f64(&self) -> f64;
+    fn is_finite(&self) -> bool;
     fn ln(&self) -> Self;
     fn max(&self, x: Self) -> Self;
     fn powf(&self, x: Self) -> Self;
     fn sqrt(&self) -> Self;
+
+    // slice kernels for the inner loops, which use SIMD with the simd feature
+
+    fn slice_sum(x: &[Self]) -> Self {
+        scalar::sum(x)
+    }
+
+    fn slice_dot(x: &[Self], y: &[Self]) -> Self {
+        scalar::dot(x, y)
+    }
+
+    fn slice_sub(out: &mut [Self], x: &[Self], y: &[Self]) {
+        scalar::sub(out, x, y)
+    }
+
+    fn slice_div(x: &mut [Self], d: Self) {
+        scalar::div(x, d)
+    }
+
+    fn tricube_weights(w: &mut [Self], d0: Self, h: Self) {
+        scalar::tricube_weights(w, d0, h)
+    }
+
+    fn weighted_moments(w: &[Self], rw: Option<&[Self]>, d0: Self, y: &[Self]) -> [Self; 5] {
+        scalar::weighted_moments(w, rw, d0, y)
+    }
+}
+
+pub(crate) mod scalar {
+    use super::Float;
+
+    pub(crate) fn sum<T: Float>(x: &[T]) -> T {
+        x.iter().copied().sum()
+    }
+
+    pub(crate) fn dot<T: Float>(x: &[T], y: &[T]) -> T {
+        x.iter().zip(y).map(|(&a, &b)| a * b).sum()
+    }
+
+    // out = x - y
+    pub(crate) fn sub<T: Float>(out: &mut [T], x: &[T], y: &[T]) {
+        for ((o, &a), &b) in out.iter_mut().zip(x).zip(y) {
+            *o = a - b;
+        }
+    }
+
+    pub(crate) fn div<T: Float>(x: &mut [T], d: T) {
+        for v in x {
+            *v /= d;
+        }
+    }
+
+    // tricube weights for the offsets d0, d0 + 1, ... from the fitted point
+    pub(crate) fn tricube_weights<T: Float>(w: &mut [T], d0: T, h: T) {
+        let h9 = T::from_f64(0.999) * h;
+        let h1 = T::from_f64(0.001) * h;
+        for (j, wj) in w.iter_mut().enumerate() {
+            let r = (d0 + T::from_usize(j)).abs();
+            *wj = if r <= h1 {
+                T::one()
+            } else if r <= h9 {
+                let u = r / h;
+                let t = T::one() - u * u * u;
+                t * t * t
+            } else {
+                T::zero()
+            };
+        }
+    }
+
+    // sums of w, w d, w d^2, w y, and w d y for the offsets d0, d0 + 1, ...,
+    // with the weights multiplied by the robustness weights if given
+    pub(crate) fn weighted_moments<T: Float>(w: &[T], rw: Option<&[T]>, d0: T, y: &[T]) -> [T; 5] {
+        let mut sums = [T::zero(); 5];
+        for (j, (&k, &v)) in w.iter().zip(y).enumerate() {
+            let w = match rw {
+                Some(rw) => k * rw[j],
+                None => k,
+            };
+            let d = d0 + T::from_usize(j);
+            sums[0] += w;
+            sums[1] += w * d;
+            sums[2] += w * d * d;
+            sums[3] += w * v;
+            sums[4] += w * d * v;
+        }
+        sums
+    }
+}
+
+// overrides the slice kernels with SIMD versions
+#[cfg(feature = "simd")]
+macro_rules! simd_kernels {
+    ($t:ident) => {
+        fn slice_sum(x: &[Self]) -> Self {
+            crate::simd::$t::sum(x)
+        }
+
+        fn slice_dot(x: &[Self], y: &[Self]) -> Self {
+            crate::simd::$t::dot(x, y)
+        }
+
+        fn slice_sub(out: &mut [Self], x: &[Self], y: &[Self]) {
+            crate::simd::$t::sub(out, x, y)
+        }
+
+        fn slice_div(x: &mut [Self], d: Self) {
+            crate::simd::$t::div(x, d)
+        }
+
+        fn tricube_weights(w: &mut [Self], d0: Self, h: Self) {
+            crate::simd::$t::tricube_weights(w, d0, h)
+        }
+
+        fn weighted_moments(w: &[Self], rw: Option<&[Self]>, d0: Self, y: &[Self]) -> [Self; 5] {
+            crate::simd::$t::weighted_moments(w, rw, d0, y)
+        }
+    };
 }
 
 impl Float for f32 {
@@ -64,6 +183,10 @@ impl Float for f32 {
         todo!()
     }
 
+    fn is_finite(&self) -> bool {
+        f32::is_finite(*self)
+    }
+
     fn max(&self, other: Self) -> Self {
         f32::max(*self, other)
     }
@@ -87,6 +210,9 @@ impl Float for f32 {
     fn sqrt(&self) -> Self {
         core::f32::math::sqrt(*self)
     }
+
+    #[cfg(feature = "simd")]
+    simd_kernels!(f32);
 }
 
 impl Float for f64 {
@@ -124,6 +250,10 @@ impl Float for f64 {
         todo!()
     }
 
+    fn is_finite(&self) -> bool {
+        f64::is_finite(*self)
+    }
+
     fn max(&self, other: Self) -> Self {
         f64::max(*self, other)
     }
@@ -147,4 +277,7 @@ impl Float for f64 {
     fn sqrt(&self) -> Self {
         core::f64::math::sqrt(*self)
     }
+
+    #[cfg(feature = "simd")]
+    simd_kernels!(f64);
 }
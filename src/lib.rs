@@ -1,6 +1,7 @@
 #![doc = include_str!("../README.md")]
 #![cfg_attr(not(feature = "std"), no_std)]
 #![cfg_attr(not(feature = "std"), feature(core_float_math))]
+#![cfg_attr(feature = "simd", feature(portable_simd))]
 
 #[cfg(feature = "alloc")]
 extern crate alloc;
@@ -11,6 +12,30 @@ mod stl;
 mod stl_impl;
 mod stl_params;
 
+#[cfg(feature = "ndarray")]
+mod array;
+#[cfg(feature = "arrow")]
+mod arrow;
+#[cfg(feature = "chrono")]
+mod calendar;
+#[cfg(feature = "alloc")]
+mod changepoint;
+#[cfg(feature = "alloc")]
+mod classical;
+#[cfg(feature = "alloc")]
+mod decomposer;
+#[cfg(feature = "alloc")]
+mod diagnostics;
+#[cfg(feature = "alloc")]
+mod extension;
+#[cfg(feature = "alloc")]
+mod features;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(any(feature = "arrow", feature = "chrono", feature = "polars"))]
+mod missing;
+#[cfg(feature = "alloc")]
+mod model;
 #[cfg(feature = "alloc")]
 mod mstl;
 #[cfg(feature = "alloc")]
@@ -20,15 +45,64 @@ mod mstl_params;
 #[cfg(feature = "alloc")]
 mod mstl_result;
 #[cfg(feature = "alloc")]
+mod pattern;
+#[cfg(feature = "polars")]
+mod polars;
+#[cfg(feature = "alloc")]
+mod regression;
+#[cfg(feature = "std")]
+mod robust_stl;
+#[cfg(feature = "simd")]
+mod simd;
+#[cfg(feature = "alloc")]
 mod stl_result;
+#[cfg(feature = "alloc")]
+mod str;
+#[cfg(feature = "alloc")]
+mod str_impl;
+#[cfg(feature = "alloc")]
+mod str_result;
+#[cfg(feature = "alloc")]
+mod vintage;
+#[cfg(feature = "wasm")]
+mod wasm;
 
-pub use error::Error;
+pub use error::{Error, ParameterValue};
 pub use float::Float;
 pub use stl::Stl;
-pub use stl_params::StlParams;
+pub use stl_params::{SeasonalSmoother, StlParams};
 
 #[cfg(feature = "alloc")]
-pub use {mstl::Mstl, mstl_params::MstlParams, mstl_result::MstlResult, stl_result::StlResult};
+pub use {
+    changepoint::{
+        ChangepointMethod, ChangepointModel, ChangepointParams, ChangepointResult, Segment,
+    },
+    classical::{Classical, ClassicalModel, ClassicalParams},
+    decomposer::{Decomposer, Decomposition},
+    diagnostics::{PortmanteauTest, RemainderStats},
+    features::{MstlFeatures, StlFeatures},
+    model::{MstlModel, StlModel},
+    mstl::Mstl,
+    mstl_params::MstlParams,
+    mstl_result::MstlResult,
+    pattern::SeasonalPattern,
+    stl_result::StlResult,
+    str::{Str, StrParams},
+    str_result::StrResult,
+    vintage::{RevisionStats, Vintages},
+};
+
+#[cfg(feature = "std")]
+pub use robust_stl::{RobustStl, RobustStlParams};
+
+#[cfg(feature = "arrow")]
+pub use arrow::{MstlArrowResult, StlArrowResult};
+
+#[cfg(feature = "chrono")]
+pub use calendar::{MstlCalendarResult, Seasonality};
+
+#[cfg(feature = "ndarray")]
+pub use array::{MstlArrayResult, StlArrayResult};
 
 /// Creates a new set of STL parameters.
 pub fn params() -> StlParams {
@@ -0,0 +1,25 @@
+use super::{Error, Float};
+
+// fills missing values by linear interpolation between the nearest valid values
+pub(crate) fn interpolate<T: Float>(series: &mut [T], valid: &[usize]) -> Result<(), Error> {
+    if valid.is_empty() {
+        return Err(Error::NoValidValues);
+    }
+
+    let first = valid[0];
+    let last = valid[valid.len() - 1];
+    for i in 0..first {
+        series[i] = series[first];
+    }
+    for i in last + 1..series.len() {
+        series[i] = series[last];
+    }
+    for w in valid.windows(2) {
+        let (a, b) = (w[0], w[1]);
+        let delta = (series[b] - series[a]) / T::from_usize(b - a);
+        for i in a + 1..b {
+            series[i] = series[a] + delta * T::from_usize(i - a);
+        }
+    }
+    Ok(())
+}
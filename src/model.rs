@@ -0,0 +1,210 @@
+use alloc::vec::Vec;
+
+use super::{Error, Float, MstlParams, MstlResult, StlParams, StlResult};
+
+/// A fitted STL model that deseasonalizes new observations.
+#[derive(Clone, Debug)]
+pub struct StlModel<T: Float = f32> {
+    result: StlResult<T>,
+    projected: Vec<T>,
+}
+
+/// A fitted MSTL model that deseasonalizes new observations.
+#[derive(Clone, Debug)]
+pub struct MstlModel<T: Float = f32> {
+    result: MstlResult<T>,
+    projected: Vec<Vec<T>>,
+}
+
+impl StlParams {
+    /// Decomposes a time series and returns a model for new observations.
+    pub fn fit_model<T: Float>(&self, series: &[T], period: usize) -> Result<StlModel<T>, Error> {
+        let (result, projected) = self.fit_projected(series, period)?;
+        Ok(StlModel { result, projected })
+    }
+}
+
+impl MstlParams {
+    /// Decomposes a time series and returns a model for new observations.
+    pub fn fit_model<T: Float>(
+        &self,
+        series: &[T],
+        periods: &[usize],
+    ) -> Result<MstlModel<T>, Error> {
+        if self.lambda.is_some() {
+            return Err(Error::IncompatibleParameters {
+                name: "lambda",
+                other: "fit_model",
+            });
+        }
+        let (result, projected) = self.fit_projected(series, periods)?;
+        Ok(MstlModel { result, projected })
+    }
+}
+
+impl<T: Float> StlModel<T> {
+    /// Returns the decomposition of the series used to fit the model.
+    pub fn result(&self) -> &StlResult<T> {
+        &self.result
+    }
+
+    /// Returns the projected seasonal component for the cycle after the series.
+    pub fn projected_seasonal(&self) -> &[T] {
+        &self.projected
+    }
+
+    /// Returns the seasonal component at a position, where zero is the start of the series.
+    ///
+    /// Positions past the end of the series repeat the projected cycle.
+    pub fn seasonal_at(&self, position: usize) -> T {
+        seasonal_at(self.result.seasonal(), &self.projected, position)
+    }
+
+    /// Removes the seasonal component from observations starting at a position.
+    pub fn transform(&self, start: usize, values: &[T]) -> Vec<T> {
+        apply(values, start, |v, i| v - self.seasonal_at(i))
+    }
+
+    /// Adds the seasonal component back to observations starting at a position.
+    pub fn inverse_transform(&self, start: usize, values: &[T]) -> Vec<T> {
+        apply(values, start, |v, i| v + self.seasonal_at(i))
+    }
+}
+
+impl<T: Float> MstlModel<T> {
+    /// Returns the decomposition of the series used to fit the model.
+    pub fn result(&self) -> &MstlResult<T> {
+        &self.result
+    }
+
+    /// Returns the projected seasonal components for the cycle after the series.
+    pub fn projected_seasonal(&self) -> &[Vec<T>] {
+        &self.projected
+    }
+
+    /// Returns the sum of the seasonal components at a position, where zero is the start of the series.
+    ///
+    /// Positions past the end of the series repeat the projected cycles.
+    pub fn seasonal_at(&self, position: usize) -> T {
+        self.result
+            .seasonal()
+            .iter()
+            .zip(&self.projected)
+            .map(|(s, p)| seasonal_at(s, p, position))
+            .sum()
+    }
+
+    /// Removes the seasonal components from observations starting at a position.
+    pub fn transform(&self, start: usize, values: &[T]) -> Vec<T> {
+        apply(values, start, |v, i| v - self.seasonal_at(i))
+    }
+
+    /// Adds the seasonal components back to observations starting at a position.
+    pub fn inverse_transform(&self, start: usize, values: &[T]) -> Vec<T> {
+        apply(values, start, |v, i| v + self.seasonal_at(i))
+    }
+}
+
+fn seasonal_at<T: Float>(seasonal: &[T], projected: &[T], position: usize) -> T {
+    if position < seasonal.len() {
+        seasonal[position]
+    } else {
+        projected[(position - seasonal.len()) % projected.len()]
+    }
+}
+
+fn apply<T: Float, F: Fn(T, usize) -> T>(values: &[T], start: usize, f: F) -> Vec<T> {
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, &v)| f(v, start + i))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stl::test_helpers::*;
+    use crate::{Error, Mstl, Stl};
+    use alloc::{vec, vec::Vec};
+
+    fn generate_series() -> Vec<f32> {
+        vec![
+            5.0, 9.0, 2.0, 9.0, 0.0, 6.0, 3.0, 8.0, 5.0, 8.0, 7.0, 8.0, 8.0, 0.0, 2.0, 5.0, 0.0,
+            5.0, 6.0, 7.0, 3.0, 6.0, 1.0, 4.0, 4.0, 4.0, 3.0, 7.0, 5.0, 8.0,
+        ]
+    }
+
+    #[test]
+    fn test_stl() {
+        let series = generate_series();
+        let model = Stl::params().fit_model(&series, 7).unwrap();
+        let expected = Stl::fit(&series, 7).unwrap();
+        assert_elements_in_delta(expected.seasonal(), model.result().seasonal());
+
+        let projected = model.projected_seasonal();
+        assert_eq!(7, projected.len());
+        assert_in_delta(projected[0], model.seasonal_at(30));
+        assert_in_delta(projected[0], model.seasonal_at(37));
+        assert_in_delta(expected.seasonal()[29], model.seasonal_at(29));
+    }
+
+    #[test]
+    fn test_stl_projection() {
+        let pattern = [1.0, 3.0, -2.0, 0.5, 2.0, -1.5, -3.0];
+        let series = (0..70)
+            .map(|i| pattern[i % 7] + 0.1 * i as f32)
+            .collect::<Vec<f32>>();
+        let model = Stl::params().fit_model(&series, 7).unwrap();
+
+        // the projected cycle continues the seasonal pattern for each phase
+        for (i, &v) in model.projected_seasonal().iter().enumerate() {
+            assert!((v - pattern[i]).abs() < 0.05);
+        }
+    }
+
+    #[test]
+    fn test_stl_transform() {
+        let model = Stl::params().fit_model(&generate_series(), 7).unwrap();
+        let values = [4.0, 6.0, 5.0];
+        let adjusted = model.transform(30, &values);
+        assert_in_delta(values[1] - model.projected_seasonal()[1], adjusted[1]);
+        assert_elements_in_delta(&values, &model.inverse_transform(30, &adjusted));
+
+        let fitted = model.transform(0, &generate_series());
+        let result = model.result();
+        for (i, &v) in fitted.iter().enumerate() {
+            assert_in_delta(result.trend()[i] + result.remainder()[i], v);
+        }
+    }
+
+    #[test]
+    fn test_mstl() {
+        let series = generate_series();
+        let model = Mstl::params().fit_model(&series, &[6, 10]).unwrap();
+        let expected = Mstl::fit(&series, &[6, 10]).unwrap();
+        assert_elements_in_delta(expected.trend(), model.result().trend());
+
+        let projected = model.projected_seasonal();
+        assert_eq!(6, projected[0].len());
+        assert_eq!(10, projected[1].len());
+        assert_in_delta(projected[0][1] + projected[1][1], model.seasonal_at(31));
+
+        let values = [4.0, 6.0, 5.0];
+        let adjusted = model.transform(30, &values);
+        assert_elements_in_delta(&values, &model.inverse_transform(30, &adjusted));
+    }
+
+    #[test]
+    fn test_mstl_lambda() {
+        let result = Mstl::params()
+            .lambda(0.5)
+            .fit_model(&generate_series(), &[6, 10]);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::IncompatibleParameters {
+                name: "lambda",
+                other: "fit_model",
+            }
+        );
+    }
+}
@@ -18,9 +18,10 @@ impl Mstl {
 #[cfg(test)]
 mod tests {
     use crate::stl::test_helpers::*;
-    use crate::{Error, Mstl, Stl};
+    use crate::{Error, Mstl, ParameterValue, Stl};
     use alloc::{vec, vec::Vec};
 
+    #[allow(clippy::needless_return)]
     fn generate_series() -> Vec<f32> {
         return vec![
             5.0, 9.0, 2.0, 9.0, 0.0, 6.0, 3.0, 8.0, 5.0, 8.0, 7.0, 8.0, 8.0, 0.0, 2.0, 5.0, 0.0,
@@ -176,7 +177,11 @@ mod tests {
         let result = Mstl::params().lambda(2.0).fit(&generate_series(), &[6, 10]);
         assert_eq!(
             result.unwrap_err(),
-            Error::Parameter("lambda must be between 0 and 1")
+            Error::InvalidParameter {
+                name: "lambda",
+                value: ParameterValue::Float(2.0),
+                reason: "must be between 0 and 1",
+            }
         );
     }
 
@@ -186,7 +191,11 @@ mod tests {
         let result = Mstl::fit(&generate_series(), &periods);
         assert_eq!(
             result.unwrap_err(),
-            Error::Parameter("periods must not be empty")
+            Error::InvalidParameter {
+                name: "periods",
+                value: ParameterValue::Integer(0),
+                reason: "must not be empty",
+            }
         );
     }
 
@@ -195,7 +204,11 @@ mod tests {
         let result = Mstl::fit(&generate_series(), &[1]);
         assert_eq!(
             result.unwrap_err(),
-            Error::Parameter("period must be at least 2")
+            Error::InvalidParameter {
+                name: "period",
+                value: ParameterValue::Integer(1),
+                reason: "must be at least 2",
+            }
         );
     }
 
@@ -204,10 +217,291 @@ mod tests {
         let result = Mstl::fit(&generate_series(), &[16]);
         assert_eq!(
             result.unwrap_err(),
-            Error::Series("series has less than two periods")
+            Error::SeriesTooShort {
+                len: 30,
+                min_len: 32
+            }
         );
     }
 
+    #[test]
+    fn test_bad_iterations() {
+        let result = Mstl::params()
+            .iterations(0)
+            .fit(&generate_series(), &[6, 10]);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::InvalidParameter {
+                name: "iterations",
+                value: ParameterValue::Integer(0),
+                reason: "must be at least 1",
+            }
+        );
+    }
+
+    #[test]
+    fn test_bad_seasonal_lengths() {
+        let result = Mstl::params()
+            .seasonal_lengths(&[7, 1])
+            .fit(&generate_series(), &[6, 10]);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::InvalidParameter {
+                name: "seasonal_lengths",
+                value: ParameterValue::Integer(1),
+                reason: "entries must be at least 3",
+            }
+        );
+    }
+
+    #[test]
+    fn test_seasonal_lengths_mismatch() {
+        let result = Mstl::params()
+            .seasonal_lengths(&[7])
+            .fit(&generate_series(), &[6, 10]);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::InvalidParameter {
+                name: "seasonal_lengths",
+                value: ParameterValue::Integer(1),
+                reason: "must have the same length as periods",
+            }
+        );
+    }
+
+    #[test]
+    fn test_bad_stl_params() {
+        let result = Mstl::params()
+            .stl_params(Stl::params().trend_jump(0).clone())
+            .fit(&generate_series(), &[6, 10]);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::InvalidParameter {
+                name: "trend_jump",
+                value: ParameterValue::Integer(0),
+                reason: "must be at least 1",
+            }
+        );
+    }
+
+    fn generate_holiday_series() -> (Vec<f64>, Vec<f64>) {
+        let pattern = [1.0, 3.0, -2.0, 0.5, 2.0, -1.5, -3.0];
+        let mut holidays = vec![0.0; 120];
+        for i in [10, 45, 80, 100] {
+            holidays[i] = 1.0;
+        }
+        let series = (0..120)
+            .map(|i| 5.0 + 0.05 * i as f64 + pattern[i % 7] + 8.0 * holidays[i])
+            .collect();
+        (series, holidays)
+    }
+
+    #[test]
+    fn test_regressors() {
+        let (series, holidays) = generate_holiday_series();
+        let result = Mstl::params()
+            .regressors(&[holidays])
+            .fit(&series, &[7])
+            .unwrap();
+        let coefficients = result.coefficients().unwrap();
+        assert_eq!(1, coefficients.len());
+        assert!((coefficients[0] - 8.0).abs() < 0.5, "{}", coefficients[0]);
+
+        let regression = result.regression().unwrap();
+        assert_in_delta(coefficients[0], regression[10]);
+        assert_in_delta(0.0, regression[11]);
+        for (i, &v) in series.iter().enumerate() {
+            assert_in_delta(
+                v,
+                result.seasonal()[0][i] + result.trend()[i] + regression[i] + result.remainder()[i],
+            );
+        }
+    }
+
+    #[test]
+    fn test_no_regressors() {
+        let result = Mstl::fit(&generate_series(), &[6, 10]).unwrap();
+        assert!(result.regression().is_none());
+        assert!(result.coefficients().is_none());
+    }
+
+    #[test]
+    fn test_regressors_length() {
+        let (series, _) = generate_holiday_series();
+        let result = Mstl::params()
+            .regressors(&[vec![0.0; 10]])
+            .fit(&series, &[7]);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::InvalidParameter {
+                name: "regressors",
+                value: ParameterValue::Integer(10),
+                reason: "must have the same length as series",
+            }
+        );
+    }
+
+    #[test]
+    fn test_regressors_collinear() {
+        let (series, holidays) = generate_holiday_series();
+        let doubled = holidays.iter().map(|v| v * 2.0).collect();
+        let result = Mstl::params()
+            .regressors(&[holidays, doubled])
+            .fit(&series, &[7]);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::InvalidParameter {
+                name: "regressors",
+                value: ParameterValue::Integer(1),
+                reason: "must be linearly independent",
+            }
+        );
+    }
+
+    #[test]
+    fn test_regressors_not_converged() {
+        // the first iteration always moves the coefficients away from zero
+        let (series, holidays) = generate_holiday_series();
+        let result = Mstl::params()
+            .regressors(&[holidays])
+            .regression_iterations(1)
+            .fit(&series, &[7]);
+        assert_eq!(result.unwrap_err(), Error::NotConverged { iterations: 1 });
+    }
+
+    #[test]
+    fn test_regression_iterations_zero() {
+        let (series, holidays) = generate_holiday_series();
+        let result = Mstl::params()
+            .regressors(&[holidays])
+            .regression_iterations(0)
+            .fit(&series, &[7]);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::InvalidParameter {
+                name: "regression_iterations",
+                value: ParameterValue::Integer(0),
+                reason: "must be at least 1",
+            }
+        );
+    }
+
+    #[test]
+    fn test_regressors_lambda() {
+        let (series, holidays) = generate_holiday_series();
+        let result = Mstl::params()
+            .lambda(0.5)
+            .regressors(&[holidays])
+            .fit(&series, &[7]);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::IncompatibleParameters {
+                name: "lambda",
+                other: "regressors",
+            }
+        );
+    }
+
+    // a sine with a period of 100, by rotating a hundredth of a turn at a time
+    fn generate_long_seasonality() -> Vec<f64> {
+        let (step_sin, step_cos) = (0.06279051952931337, 0.9980267284282716);
+        let (mut sin, mut cos) = (0.0, 1.0);
+        (0..400)
+            .map(|_| {
+                let v = 3.0 * sin;
+                (sin, cos) = (
+                    sin * step_cos + cos * step_sin,
+                    cos * step_cos - sin * step_sin,
+                );
+                v
+            })
+            .collect()
+    }
+
+    // a short seasonality plus a smooth long one
+    fn generate_long_series() -> Vec<f64> {
+        generate_long_seasonality()
+            .iter()
+            .enumerate()
+            .map(|(i, v)| 10.0 + 0.01 * i as f64 + [1.0, -1.0, 2.0, -2.0][i % 4] + v)
+            .collect()
+    }
+
+    #[test]
+    fn test_fourier_terms() {
+        let series = generate_long_series();
+        let result = Mstl::params()
+            .fourier_terms(&[0, 2])
+            .fit(&series, &[4, 100])
+            .unwrap();
+        let expected = generate_long_seasonality();
+        for (i, &v) in result.seasonal()[1].iter().enumerate() {
+            assert!((v - expected[i]).abs() < 0.01, "{} {}", i, v);
+        }
+        // the Fourier terms are fit after removing the trend, so it does not leak into them
+        for (i, &v) in result.trend().iter().enumerate() {
+            assert!((v - (10.0 + 0.01 * i as f64)).abs() < 0.01, "{} {}", i, v);
+        }
+        for (i, &v) in result.seasonal()[0].iter().enumerate().skip(8).take(384) {
+            assert!(
+                (v - [1.0, -1.0, 2.0, -2.0][i % 4]).abs() < 0.1,
+                "{} {}",
+                i,
+                v
+            );
+        }
+        for (i, &v) in series.iter().enumerate() {
+            let s = result.seasonal()[0][i] + result.seasonal()[1][i];
+            assert_in_delta(v, s + result.trend()[i] + result.remainder()[i]);
+        }
+    }
+
+    #[test]
+    fn test_fourier_terms_too_many() {
+        let result = Mstl::params()
+            .fourier_terms(&[0, 5])
+            .fit(&generate_series(), &[6, 10]);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::InvalidParameter {
+                name: "fourier_terms",
+                value: ParameterValue::Integer(5),
+                reason: "entries must be less than half the period",
+            }
+        );
+    }
+
+    #[test]
+    fn test_fourier_terms_no_loess() {
+        let result = Mstl::params()
+            .fourier_terms(&[2, 4])
+            .fit(&generate_series(), &[6, 10]);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::InvalidParameter {
+                name: "fourier_terms",
+                value: ParameterValue::Integer(2),
+                reason: "must leave at least one period for loess",
+            }
+        );
+    }
+
+    #[test]
+    fn test_nan() {
+        let mut series = generate_series();
+        series[10] = f32::NAN;
+        let result = Mstl::fit(&series, &[6, 10]);
+        assert_eq!(result.unwrap_err(), Error::NonFiniteValue { index: 10 });
+    }
+
+    #[test]
+    fn test_nan_unchecked() {
+        let mut series = generate_series();
+        series[10] = f32::NAN;
+        let result = Mstl::params().check_finite(false).fit(&series, &[6, 10]);
+        assert!(result.is_ok());
+    }
+
     #[test]
     fn test_seasonal_strength() {
         let mut stl_params = Stl::params();
@@ -253,4 +547,36 @@ mod tests {
             .unwrap();
         assert_in_delta(1.0, result.trend_strength());
     }
+
+    #[test]
+    fn test_features() {
+        let result = Mstl::fit(&generate_series(), &[6, 10]).unwrap();
+        let features = result.features();
+        assert_in_delta(0.22109716438795513, features.trend_strength);
+        assert_elements_in_delta(
+            &[0.5616238300349186, 0.5096177843995154],
+            &features.seasonal_strength,
+        );
+        assert_in_delta(0.012753057718629734, features.spikiness);
+        assert_in_delta(-1.1838976300298933, features.linearity);
+        assert_in_delta(1.610191091553488, features.curvature);
+        assert_eq!(vec![3, 1], features.seasonal_peak);
+        assert_eq!(vec![4, 3], features.seasonal_trough);
+        assert_in_delta(-0.5119229823591684, features.remainder_acf1);
+        assert_in_delta(1.1116031593182472, features.remainder_acf10);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_ljung_box() {
+        let mut stl_params = Stl::params();
+        stl_params.seasonal_length(7);
+        let result = Mstl::params()
+            .stl_params(stl_params)
+            .fit(&generate_series(), &[7])
+            .unwrap();
+        let test = result.ljung_box(5);
+        assert_in_delta(15.021418832451033, test.statistic);
+        assert_in_delta(0.010271209552463651, test.p_value);
+    }
 }
@@ -2,8 +2,10 @@
 // MSTL: A Seasonal-Trend Decomposition Algorithm for Time Series with Multiple Seasonal Patterns.
 // arXiv:2107.13462 [stat.AP]. https://doi.org/10.48550/arXiv.2107.13462
 
-use alloc::vec::Vec;
+use alloc::{vec, vec::Vec};
 
+use super::regression::least_squares;
+use super::stl_params::projection;
 use super::{Error, Float, StlParams};
 
 #[allow(clippy::too_many_arguments)]
@@ -13,12 +15,14 @@ pub fn mstl<T: Float>(
     iterate: usize,
     lambda: Option<f32>,
     swin: &Option<Vec<usize>>,
+    fourier: &Option<Vec<usize>>,
     stl_params: &StlParams,
     seasonality: &mut [Vec<T>],
     trend: &mut [T],
     remainder: &mut [T],
     weights: &mut [T],
     work: &mut [T],
+    projected: &mut [Vec<T>],
 ) -> Result<(), Error> {
     let k = x.len();
 
@@ -27,6 +31,15 @@ pub fn mstl<T: Float>(
     let mut indices: Vec<usize> = (0..seas_ids.len()).collect();
     indices.sort_by_key(|&i| &seas_ids[i]);
 
+    // fit Fourier terms before loess in each iteration, so the last loess fit gives the trend
+    let terms = |idx: usize| fourier.as_ref().map_or(0, |f| f[idx]);
+    let mut order = indices.iter().copied().enumerate().collect::<Vec<_>>();
+    order.sort_by_key(|&(_, idx)| terms(idx) == 0);
+    let columns = order
+        .iter()
+        .map(|&(_, idx)| fourier_columns(k, seas_ids[idx], terms(idx)))
+        .collect::<Vec<_>>();
+
     let mut iterate = iterate;
     if seas_ids.len() == 1 {
         iterate = 1;
@@ -38,16 +51,35 @@ pub fn mstl<T: Float>(
         x.to_vec()
     };
 
+    if columns.first().is_some_and(|c| !c.is_empty()) {
+        initial_trend(&deseas, *seas_ids.iter().max().unwrap(), trend);
+    }
+
     let mut params = stl_params.clone();
 
     for j in 0..iterate {
-        for (i, &idx) in indices.iter().enumerate() {
+        for (&(i, idx), columns) in order.iter().zip(&columns) {
             if j > 0 {
                 for (d, s) in deseas.iter_mut().zip(&seasonality[idx]) {
                     *d += *s;
                 }
             }
 
+            if terms(idx) > 0 {
+                fourier_fit(
+                    &deseas,
+                    trend,
+                    seas_ids[idx],
+                    columns,
+                    &mut seasonality[idx],
+                    &mut projected[idx],
+                )?;
+                for (d, s) in deseas.iter_mut().zip(&seasonality[idx]) {
+                    *d -= *s;
+                }
+                continue;
+            }
+
             if let Some(sw) = &swin {
                 params.seasonal_length(sw[idx]);
             } else if stl_params.ns.is_none() {
@@ -62,6 +94,7 @@ pub fn mstl<T: Float>(
                 weights,
                 work,
             )?;
+            projected[idx] = projection(&seasonality[idx], seas_ids[idx], work);
 
             for (d, s) in deseas.iter_mut().zip(&seasonality[idx]) {
                 *d -= *s;
@@ -76,6 +109,122 @@ pub fn mstl<T: Float>(
     Ok(())
 }
 
+// an intercept, followed by a sine and cosine for each harmonic
+fn fourier_columns(n: usize, period: usize, terms: usize) -> Vec<Vec<f64>> {
+    if terms == 0 {
+        return Vec::new();
+    }
+    let mut columns = Vec::with_capacity(2 * terms + 1);
+    columns.push(vec![1.0; n]);
+    for h in 1..=terms {
+        let (sin, cos) = (0..n).map(|t| harmonic(t, period, h)).unzip();
+        columns.push(sin);
+        columns.push(cos);
+    }
+    columns
+}
+
+// reduces the phase first so the angle stays accurate for long series
+fn harmonic(t: usize, period: usize, h: usize) -> (f64, f64) {
+    sin_cos_turns(((h * t) % period) as f64 / period as f64)
+}
+
+// the sine and cosine of a fraction of a turn, without std, by reducing to within an eighth
+// of a turn of a quarter turn and summing the Taylor series
+fn sin_cos_turns(turns: f64) -> (f64, f64) {
+    let quarter = (4.0 * turns + 0.5) as i64;
+    let x = 2.0 * core::f64::consts::PI * (turns - quarter as f64 / 4.0);
+    let x2 = x * x;
+    let mut sin = x;
+    let mut cos = 1.0;
+    let mut sin_term = x;
+    let mut cos_term = 1.0;
+    for k in 1..=10 {
+        let k = k as f64;
+        sin_term *= -x2 / ((2.0 * k) * (2.0 * k + 1.0));
+        cos_term *= -x2 / ((2.0 * k - 1.0) * (2.0 * k));
+        sin += sin_term;
+        cos += cos_term;
+    }
+    match quarter % 4 {
+        0 => (sin, cos),
+        1 => (cos, -sin),
+        2 => (-sin, -cos),
+        _ => (-cos, sin),
+    }
+}
+
+// regresses the series without the current trend on the Fourier terms, leaving the intercept
+// out of the seasonal component
+fn fourier_fit<T: Float>(
+    y: &[T],
+    trend: &[T],
+    period: usize,
+    columns: &[Vec<f64>],
+    seasonal: &mut [T],
+    projected: &mut Vec<T>,
+) -> Result<(), Error> {
+    let n = y.len();
+    let y = y
+        .iter()
+        .zip(trend)
+        .map(|(v, t)| (*v - *t).as_f64())
+        .collect::<Vec<f64>>();
+    let terms = (columns.len() - 1) / 2;
+    let beta = least_squares(columns, &y)
+        .map_err(|_| Error::parameter("fourier_terms", terms, "must be linearly independent"))?;
+    for (t, s) in seasonal.iter_mut().enumerate() {
+        let v = columns[1..]
+            .iter()
+            .zip(&beta[1..])
+            .map(|(c, b)| c[t] * b)
+            .sum::<f64>();
+        *s = T::from_f64(v);
+    }
+
+    projected.clear();
+    projected.extend((n..n + period).map(|t| {
+        let v = beta[1..]
+            .chunks(2)
+            .enumerate()
+            .map(|(i, b)| {
+                let (sin, cos) = harmonic(t, period, i + 1);
+                b[0] * sin + b[1] * cos
+            })
+            .sum::<f64>();
+        T::from_f64(v)
+    }));
+    Ok(())
+}
+
+// a centered moving average over the longest period, extended linearly at the ends, as the trend
+// for Fourier terms before the first loess fit
+fn initial_trend<T: Float>(y: &[T], period: usize, trend: &mut [T]) {
+    let n = y.len();
+    let mut sums = Vec::with_capacity(n + 1);
+    sums.push(0.0);
+    for v in y {
+        sums.push(sums[sums.len() - 1] + v.as_f64());
+    }
+    let average = |start: usize| (sums[start + period] - sums[start]) / period as f64;
+
+    // the series has at least two periods, so the slopes use averages a period apart
+    let last = n - period;
+    let first_slope = (average(period) - average(0)) / period as f64;
+    let last_slope = (average(last) - average(last - period)) / period as f64;
+    for (t, v) in trend.iter_mut().enumerate() {
+        let start = t as f64 - (period / 2) as f64;
+        let value = if start < 0.0 {
+            average(0) + start * first_slope
+        } else if start > last as f64 {
+            average(last) + (start - last as f64) * last_slope
+        } else {
+            average(start as usize)
+        };
+        *v = T::from_f64(value);
+    }
+}
+
 fn box_cox<T: Float>(y: &[T], lambda: T) -> Vec<T> {
     if lambda != T::zero() {
         y.iter()
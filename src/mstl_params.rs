@@ -1,15 +1,22 @@
 use alloc::{vec, vec::Vec};
 
 use super::mstl_impl::mstl;
+use super::regression::{check_regressors, least_squares};
+use super::stl_params::ensure_finite;
 use super::{Error, Float, MstlResult, StlParams};
 
 /// A set of MSTL parameters.
 #[derive(Clone, Debug)]
 pub struct MstlParams {
-    iterate: usize,
-    lambda: Option<f32>,
-    swin: Option<Vec<usize>>,
-    stl_params: StlParams,
+    pub(crate) iterate: usize,
+    pub(crate) lambda: Option<f32>,
+    pub(crate) swin: Option<Vec<usize>>,
+    pub(crate) fourier: Option<Vec<usize>>,
+    pub(crate) stl_params: StlParams,
+    pub(crate) regressors: Option<Vec<Vec<f64>>>,
+    pub(crate) regression_iterate: usize,
+    pub(crate) extension: usize,
+    pub(crate) check_finite: bool,
 }
 
 impl MstlParams {
@@ -19,7 +26,12 @@ impl MstlParams {
             iterate: 2,
             lambda: None,
             swin: None,
+            fourier: None,
             stl_params: StlParams::new(),
+            regressors: None,
+            regression_iterate: 20,
+            extension: 0,
+            check_finite: true,
         }
     }
 
@@ -41,41 +53,170 @@ impl MstlParams {
         self
     }
 
+    /// Sets the number of Fourier terms for each period, to fit by least squares instead of loess.
+    ///
+    /// Use 0 for periods to smooth with loess. This avoids large work buffers for very long periods,
+    /// like yearly seasonality in hourly data, but the seasonal pattern cannot change over time.
+    pub fn fourier_terms(&mut self, terms: &[usize]) -> &mut Self {
+        self.fourier = Some(terms.to_vec());
+        self
+    }
+
     /// Sets the STL parameters.
     pub fn stl_params(&mut self, stl_params: StlParams) -> &mut Self {
         self.stl_params = stl_params;
         self
     }
 
+    /// Sets regressors, like holiday or promotion indicators, to estimate jointly with the decomposition.
+    ///
+    /// Each regressor must have the same length as the series.
+    pub fn regressors(&mut self, regressors: &[Vec<f64>]) -> &mut Self {
+        self.regressors = Some(regressors.to_vec());
+        self
+    }
+
+    /// Sets the maximum number of backfitting iterations with regressors (20 by default).
+    ///
+    /// Fitting returns [`Error::NotConverged`] if the coefficients are still changing
+    /// after this many iterations.
+    pub fn regression_iterations(&mut self, iterations: usize) -> &mut Self {
+        self.regression_iterate = iterations;
+        self
+    }
+
+    /// Sets the number of forecasts and backcasts to extend the series with before decomposing.
+    ///
+    /// The series is extended once for all periods. Fitting returns
+    /// [`Error::IncompatibleParameters`] if an extension is set on the STL parameters instead.
+    pub fn extension(&mut self, horizon: usize) -> &mut Self {
+        self.extension = horizon;
+        self
+    }
+
+    /// Sets whether to check the series for non-finite values (enabled by default).
+    pub fn check_finite(&mut self, check_finite: bool) -> &mut Self {
+        self.check_finite = check_finite;
+        self
+    }
+
     /// Decomposes a time series.
     pub fn fit<T: Float>(&self, series: &[T], periods: &[usize]) -> Result<MstlResult<T>, Error> {
+        self.fit_projected(series, periods)
+            .map(|(result, _)| result)
+    }
+
+    // also returns the seasonal components projected one cycle past the end
+    pub(crate) fn fit_projected<T: Float>(
+        &self,
+        series: &[T],
+        periods: &[usize],
+    ) -> Result<(MstlResult<T>, Vec<Vec<T>>), Error> {
+        // the inner fits do not extend the series
+        if self.stl_params.extension > 0 {
+            return Err(Error::IncompatibleParameters {
+                name: "extension",
+                other: "stl_params",
+            });
+        }
+
+        if self.extension > 0 && !periods.is_empty() {
+            return self.fit_extended(series, periods);
+        }
+
         if periods.is_empty() {
             // TODO use Friedman's Super Smoother for trend
-            return Err(Error::Parameter("periods must not be empty"));
+            return Err(Error::parameter("periods", 0, "must not be empty"));
         }
 
-        if periods.iter().any(|&v| v < 2) {
-            return Err(Error::Parameter("period must be at least 2"));
+        if let Some(&np) = periods.iter().find(|&&v| v < 2) {
+            return Err(Error::parameter("period", np, "must be at least 2"));
         }
 
-        for np in periods {
-            if series.len() / 2 < *np {
-                return Err(Error::Series("series has less than two periods"));
+        for &np in periods {
+            if series.len() / 2 < np {
+                return Err(Error::SeriesTooShort {
+                    len: series.len(),
+                    min_len: 2 * np,
+                });
             }
         }
 
+        if self.check_finite {
+            ensure_finite(series)?;
+        }
+
         if let Some(lambda) = self.lambda {
             if !(0.0..=1.0).contains(&lambda) {
-                return Err(Error::Parameter("lambda must be between 0 and 1"));
+                return Err(Error::float_parameter(
+                    "lambda",
+                    lambda as f64,
+                    "must be between 0 and 1",
+                ));
             }
         }
 
+        if self.iterate == 0 {
+            return Err(Error::parameter("iterations", 0, "must be at least 1"));
+        }
+
+        if self.regression_iterate == 0 {
+            return Err(Error::parameter(
+                "regression_iterations",
+                0,
+                "must be at least 1",
+            ));
+        }
+
         if let Some(swin) = &self.swin {
+            if let Some(&ns) = swin.iter().find(|&&v| v < 3) {
+                return Err(Error::parameter(
+                    "seasonal_lengths",
+                    ns,
+                    "entries must be at least 3",
+                ));
+            }
             if swin.len() != periods.len() {
-                return Err(Error::Parameter(
-                    "seasonal_lengths must have the same length as periods",
+                return Err(Error::parameter(
+                    "seasonal_lengths",
+                    swin.len(),
+                    "must have the same length as periods",
+                ));
+            }
+        }
+
+        if let Some(fourier) = &self.fourier {
+            if fourier.len() != periods.len() {
+                return Err(Error::parameter(
+                    "fourier_terms",
+                    fourier.len(),
+                    "must have the same length as periods",
                 ));
             }
+            if let Some((&k, _)) = fourier.iter().zip(periods).find(|(&k, &np)| 2 * k >= np) {
+                return Err(Error::parameter(
+                    "fourier_terms",
+                    k,
+                    "entries must be less than half the period",
+                ));
+            }
+            if fourier.iter().all(|&k| k > 0) {
+                return Err(Error::parameter(
+                    "fourier_terms",
+                    fourier.len(),
+                    "must leave at least one period for loess",
+                ));
+            }
+        }
+
+        if let Some(regressors) = &self.regressors {
+            check_regressors(regressors, series.len())?;
+            if self.lambda.is_some() {
+                return Err(Error::IncompatibleParameters {
+                    name: "lambda",
+                    other: "regressors",
+                });
+            }
         }
 
         let n = series.len();
@@ -86,27 +227,120 @@ impl MstlParams {
         let mut trend = vec![T::zero(); n];
         let mut remainder = vec![T::zero(); n];
         let mut weights = vec![T::zero(); n];
-        let mut work = vec![T::zero(); (n + 2 * periods.iter().max().unwrap()) * 5];
-
-        mstl(
-            series,
-            periods,
-            self.iterate,
-            self.lambda,
-            &self.swin,
-            &self.stl_params,
-            &mut seasonal,
-            &mut trend,
-            &mut remainder,
-            &mut weights,
-            &mut work,
-        )?;
-
-        Ok(MstlResult {
+        let mut work = vec![T::zero(); self.work_len(n, periods)];
+        let mut projected = vec![Vec::new(); periods.len()];
+
+        let regressors = match &self.regressors {
+            Some(regressors) if !regressors.is_empty() => regressors,
+            _ => {
+                mstl(
+                    series,
+                    periods,
+                    self.iterate,
+                    self.lambda,
+                    &self.swin,
+                    &self.fourier,
+                    &self.stl_params,
+                    &mut seasonal,
+                    &mut trend,
+                    &mut remainder,
+                    &mut weights,
+                    &mut work,
+                    &mut projected,
+                )?;
+
+                let result = MstlResult {
+                    seasonal,
+                    trend,
+                    remainder,
+                    periods: periods.to_vec(),
+                    regression: None,
+                    coefficients: None,
+                };
+                return Ok((result, projected));
+            }
+        };
+
+        // backfitting: decompose the series minus the regression component,
+        // then regress the series minus the decomposition on the regressors
+        let mut coefficients = vec![0.0; regressors.len()];
+        let mut regression = vec![T::zero(); n];
+        let mut adjusted = series.to_vec();
+        let mut converged = false;
+        for _ in 0..self.regression_iterate {
+            mstl(
+                &adjusted,
+                periods,
+                self.iterate,
+                None,
+                &self.swin,
+                &self.fourier,
+                &self.stl_params,
+                &mut seasonal,
+                &mut trend,
+                &mut remainder,
+                &mut weights,
+                &mut work,
+                &mut projected,
+            )?;
+
+            let partial = (0..n)
+                .map(|i| {
+                    let s = seasonal.iter().map(|s| s[i]).sum::<T>();
+                    (series[i] - s - trend[i]).as_f64()
+                })
+                .collect::<Vec<f64>>();
+            let next = least_squares(regressors, &partial)?;
+            let change = next
+                .iter()
+                .zip(&coefficients)
+                .map(|(a, b)| (a - b).abs())
+                .fold(0.0, f64::max);
+            let size = next.iter().map(|v| v.abs()).fold(1.0, f64::max);
+            // stop before updating so the components match the coefficients
+            if change <= 1e-6 * size {
+                converged = true;
+                break;
+            }
+            coefficients = next;
+            for i in 0..n {
+                let r = regressors
+                    .iter()
+                    .zip(&coefficients)
+                    .map(|(x, b)| x[i] * b)
+                    .sum::<f64>();
+                regression[i] = T::from_f64(r);
+                adjusted[i] = series[i] - regression[i];
+            }
+        }
+
+        if !converged {
+            return Err(Error::NotConverged {
+                iterations: self.regression_iterate,
+            });
+        }
+
+        let result = MstlResult {
             seasonal,
             trend,
             remainder,
-        })
+            periods: periods.to_vec(),
+            regression: Some(regression),
+            coefficients: Some(coefficients),
+        };
+        Ok((result, projected))
+    }
+
+    // only periods smoothed with loess need work space
+    pub(crate) fn work_len(&self, n: usize, periods: &[usize]) -> usize {
+        let max_period = periods
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| self.fourier.as_ref().map_or(0, |f| f[i]) == 0)
+            .map(|(_, &np)| np)
+            .max()
+            .unwrap_or(0);
+        (n + 2 * max_period) * 5
     }
 }
 
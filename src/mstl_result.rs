@@ -1,7 +1,13 @@
 use alloc::vec::Vec;
 
+use super::changepoint::{ChangepointParams, ChangepointResult};
+#[cfg(feature = "std")]
+use super::diagnostics::PortmanteauTest;
+use super::diagnostics::{self, RemainderStats};
+use super::features::{self, MstlFeatures};
+use super::pattern::SeasonalPattern;
 use super::stl_result::strength;
-use super::Float;
+use super::{Error, Float};
 
 /// A MSTL result.
 #[derive(Clone, Debug)]
@@ -9,6 +15,9 @@ pub struct MstlResult<T: Float = f32> {
     pub(crate) seasonal: Vec<Vec<T>>,
     pub(crate) trend: Vec<T>,
     pub(crate) remainder: Vec<T>,
+    pub(crate) periods: Vec<usize>,
+    pub(crate) regression: Option<Vec<T>>,
+    pub(crate) coefficients: Option<Vec<f64>>,
 }
 
 impl<T: Float> MstlResult<T> {
@@ -27,6 +36,16 @@ impl<T: Float> MstlResult<T> {
         &self.remainder
     }
 
+    /// Returns the regression component, if regressors were given.
+    pub fn regression(&self) -> Option<&[T]> {
+        self.regression.as_deref()
+    }
+
+    /// Returns the regression coefficients, if regressors were given.
+    pub fn coefficients(&self) -> Option<&[f64]> {
+        self.coefficients.as_deref()
+    }
+
     /// Returns the seasonal strength.
     pub fn seasonal_strength(&self) -> Vec<f64> {
         self.seasonal()
@@ -40,6 +59,71 @@ impl<T: Float> MstlResult<T> {
         strength(self.trend(), self.remainder())
     }
 
+    /// Returns MSTL-based features.
+    pub fn features(&self) -> MstlFeatures {
+        let (linearity, curvature) = features::linearity_curvature(self.trend());
+        let (seasonal_peak, seasonal_trough) = self
+            .seasonal()
+            .iter()
+            .zip(&self.periods)
+            .map(|(s, &np)| features::peak_trough(s, np))
+            .unzip();
+        let (remainder_acf1, remainder_acf10) = features::remainder_acf(self.remainder());
+        MstlFeatures {
+            trend_strength: self.trend_strength(),
+            seasonal_strength: self.seasonal_strength(),
+            spikiness: features::spikiness(self.remainder()),
+            linearity,
+            curvature,
+            seasonal_peak,
+            seasonal_trough,
+            remainder_acf1,
+            remainder_acf10,
+        }
+    }
+
+    /// Detects changepoints in the seasonally adjusted series.
+    pub fn changepoints(&self, params: &ChangepointParams) -> Result<ChangepointResult, Error> {
+        let adjusted = self
+            .trend()
+            .iter()
+            .zip(self.remainder())
+            .map(|(t, r)| *t + *r)
+            .collect::<Vec<T>>();
+        params.fit(&adjusted)
+    }
+
+    /// Returns the autocorrelation function of the remainder for lags `0..=max_lag`.
+    pub fn remainder_acf(&self, max_lag: usize) -> Vec<f64> {
+        diagnostics::acf(self.remainder(), max_lag)
+    }
+
+    /// Returns the Ljung-Box test of the remainder.
+    #[cfg(feature = "std")]
+    pub fn ljung_box(&self, lags: usize) -> PortmanteauTest {
+        diagnostics::ljung_box(self.remainder(), lags)
+    }
+
+    /// Returns the Box-Pierce test of the remainder.
+    #[cfg(feature = "std")]
+    pub fn box_pierce(&self, lags: usize) -> PortmanteauTest {
+        diagnostics::box_pierce(self.remainder(), lags)
+    }
+
+    /// Returns summary statistics of the remainder.
+    pub fn remainder_stats(&self) -> RemainderStats {
+        diagnostics::remainder_stats(self.remainder())
+    }
+
+    /// Returns the seasonal components arranged by cycle and phase.
+    pub fn seasonal_patterns(&self) -> Vec<SeasonalPattern<T>> {
+        self.seasonal
+            .iter()
+            .zip(&self.periods)
+            .map(|(s, &np)| SeasonalPattern::new(s, np))
+            .collect()
+    }
+
     /// Consumes the result, returning the seasonal components, trend component, and remainder.
     pub fn into_parts(self) -> (Vec<Vec<T>>, Vec<T>, Vec<T>) {
         (self.seasonal, self.trend, self.remainder)
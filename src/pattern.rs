@@ -0,0 +1,121 @@
+use alloc::vec::Vec;
+
+use super::Float;
+
+/// The seasonal component arranged by cycle and phase.
+#[derive(Clone, Debug)]
+pub struct SeasonalPattern<T: Float = f32> {
+    period: usize,
+    cycles: Vec<Vec<T>>,
+}
+
+impl<T: Float> SeasonalPattern<T> {
+    pub(crate) fn new(seasonal: &[T], period: usize) -> Self {
+        let cycles = seasonal.chunks(period).map(|c| c.to_vec()).collect();
+        Self { period, cycles }
+    }
+
+    /// Returns the period.
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Returns the seasonal values for each cycle, indexed by phase.
+    ///
+    /// The last cycle is shorter than the period when the series does not end on a cycle boundary.
+    pub fn cycles(&self) -> &[Vec<T>] {
+        &self.cycles
+    }
+
+    /// Returns the seasonal values for a phase across cycles (the cycle-subseries),
+    /// or `None` if the phase is not less than the period.
+    pub fn subseries(&self, phase: usize) -> Option<Vec<T>> {
+        (phase < self.period).then(|| {
+            self.cycles
+                .iter()
+                .filter_map(|c| c.get(phase).copied())
+                .collect()
+        })
+    }
+
+    /// Returns the mean seasonal value for each phase.
+    pub fn profile(&self) -> Vec<T> {
+        (0..self.period)
+            .map(|phase| {
+                let subseries = self.subseries(phase).unwrap();
+                let n = T::from_usize(subseries.len());
+                subseries.into_iter().sum::<T>() / n
+            })
+            .collect()
+    }
+
+    /// Returns the difference from the mean profile for each cycle, indexed by phase.
+    pub fn evolution(&self) -> Vec<Vec<T>> {
+        let profile = self.profile();
+        self.cycles
+            .iter()
+            .map(|c| c.iter().zip(&profile).map(|(v, p)| *v - *p).collect())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stl::test_helpers::*;
+    use crate::{Mstl, Stl};
+    use alloc::{vec, vec::Vec};
+
+    fn generate_series() -> Vec<f32> {
+        vec![
+            5.0, 9.0, 2.0, 9.0, 0.0, 6.0, 3.0, 8.0, 5.0, 8.0, 7.0, 8.0, 8.0, 0.0, 2.0, 5.0, 0.0,
+            5.0, 6.0, 7.0, 3.0, 6.0, 1.0, 4.0, 4.0, 4.0, 3.0, 7.0, 5.0, 8.0,
+        ]
+    }
+
+    #[test]
+    fn test_stl() {
+        let result = Stl::fit(&generate_series(), 7).unwrap();
+        let pattern = result.seasonal_pattern();
+        assert_eq!(7, pattern.period());
+
+        let cycles = pattern.cycles();
+        assert_eq!(5, cycles.len());
+        assert_eq!(7, cycles[3].len());
+        assert_eq!(2, cycles[4].len());
+        assert_elements_in_delta(&result.seasonal()[7..14], &cycles[1]);
+
+        let subseries = pattern.subseries(1).unwrap();
+        assert_eq!(5, subseries.len());
+        assert_eq!(4, pattern.subseries(2).unwrap().len());
+        assert_in_delta(result.seasonal()[15], subseries[2]);
+
+        let profile = pattern.profile();
+        assert_eq!(7, profile.len());
+        let expected = subseries.iter().sum::<f32>() / 5.0;
+        assert_in_delta(expected, profile[1]);
+
+        let evolution = pattern.evolution();
+        assert_eq!(5, evolution.len());
+        assert_eq!(2, evolution[4].len());
+        assert_in_delta(subseries[2] - profile[1], evolution[2][1]);
+        assert_in_delta(0.0, evolution.iter().map(|c| c[1]).sum());
+    }
+
+    #[test]
+    fn test_mstl() {
+        let result = Mstl::fit(&generate_series(), &[6, 10]).unwrap();
+        let patterns = result.seasonal_patterns();
+        assert_eq!(2, patterns.len());
+        assert_eq!(6, patterns[0].period());
+        assert_eq!(5, patterns[0].cycles().len());
+        assert_eq!(10, patterns[1].period());
+        assert_eq!(3, patterns[1].cycles().len());
+        assert_elements_in_delta(&result.seasonal()[1][10..20], &patterns[1].cycles()[1]);
+    }
+
+    #[test]
+    fn test_subseries_phase() {
+        let result = Stl::fit(&generate_series(), 7).unwrap();
+        assert_eq!(None, result.seasonal_pattern().subseries(7));
+    }
+}
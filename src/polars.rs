@@ -0,0 +1,366 @@
+use std::collections::HashMap;
+
+use polars::prelude::{Column, DataFrame, DataType, NamedFrom, Series};
+
+use super::missing;
+use super::{Error, MstlParams, StlParams};
+
+impl StlParams {
+    /// Decomposes a Polars series, treating nulls as missing values.
+    ///
+    /// Returns a data frame with `seasonal`, `trend`, `remainder`, and `weights` columns.
+    pub fn fit_polars(&self, series: &Series, period: usize) -> Result<DataFrame, Error> {
+        let (values, valid) = read_series(series)?;
+        let result = self.fit(&values, period)?;
+        let (seasonal, trend, remainder, weights) = result.into_parts();
+        data_frame(
+            vec![
+                ("seasonal".to_string(), seasonal, false),
+                ("trend".to_string(), trend, false),
+                ("remainder".to_string(), remainder, true),
+                ("weights".to_string(), weights, false),
+            ],
+            &valid,
+        )
+    }
+
+    /// Decomposes each series in a long-format data frame.
+    ///
+    /// Rows for each series id must be in time order. Returns a data frame
+    /// with the id column followed by the components, in the original row order.
+    pub fn fit_polars_grouped(
+        &self,
+        df: &DataFrame,
+        id_column: &str,
+        value_column: &str,
+        period: usize,
+    ) -> Result<DataFrame, Error> {
+        fit_grouped(df, id_column, value_column, |series| {
+            self.fit_polars(series, period)
+        })
+    }
+}
+
+impl MstlParams {
+    /// Decomposes a Polars series, treating nulls as missing values.
+    ///
+    /// Returns a data frame with `seasonal_{period}`, `trend`, and `remainder` columns,
+    /// so periods must be unique.
+    pub fn fit_polars(&self, series: &Series, periods: &[usize]) -> Result<DataFrame, Error> {
+        for (i, &np) in periods.iter().enumerate() {
+            if periods[..i].contains(&np) {
+                return Err(Error::parameter("periods", np, "must be unique"));
+            }
+        }
+        let (values, valid) = read_series(series)?;
+        let result = self.fit(&values, periods)?;
+        let (seasonal, trend, remainder) = result.into_parts();
+        let mut columns = periods
+            .iter()
+            .zip(seasonal)
+            .map(|(np, s)| (format!("seasonal_{}", np), s, false))
+            .collect::<Vec<_>>();
+        columns.push(("trend".to_string(), trend, false));
+        columns.push(("remainder".to_string(), remainder, true));
+        data_frame(columns, &valid)
+    }
+
+    /// Decomposes each series in a long-format data frame.
+    ///
+    /// Rows for each series id must be in time order. Returns a data frame
+    /// with the id column followed by the components, in the original row order.
+    pub fn fit_polars_grouped(
+        &self,
+        df: &DataFrame,
+        id_column: &str,
+        value_column: &str,
+        periods: &[usize],
+    ) -> Result<DataFrame, Error> {
+        fit_grouped(df, id_column, value_column, |series| {
+            self.fit_polars(series, periods)
+        })
+    }
+}
+
+fn read_series(series: &Series) -> Result<(Vec<f64>, Vec<bool>), Error> {
+    if !series.dtype().is_primitive_numeric() {
+        return Err(Error::InvalidColumn {
+            name: "series",
+            reason: "must be numeric",
+        });
+    }
+    let series = series
+        .cast(&DataType::Float64)
+        .map_err(|_| Error::InvalidColumn {
+            name: "series",
+            reason: "must be numeric",
+        })?;
+    let ca = series.f64().unwrap();
+
+    let mut values = Vec::with_capacity(ca.len());
+    let mut valid = Vec::with_capacity(ca.len());
+    let mut valid_indices = Vec::new();
+    for (i, v) in ca.iter().enumerate() {
+        values.push(v.unwrap_or(0.0));
+        valid.push(v.is_some());
+        if v.is_some() {
+            valid_indices.push(i);
+        }
+    }
+    if valid_indices.len() < values.len() {
+        missing::interpolate(&mut values, &valid_indices)?;
+    }
+    Ok((values, valid))
+}
+
+// sets values to null where the series is null for nullable columns
+fn data_frame(columns: Vec<(String, Vec<f64>, bool)>, valid: &[bool]) -> Result<DataFrame, Error> {
+    let columns = columns
+        .into_iter()
+        .map(|(name, values, nullable)| {
+            if nullable {
+                let values = values
+                    .into_iter()
+                    .zip(valid)
+                    .map(|(v, &ok)| ok.then_some(v))
+                    .collect::<Vec<Option<f64>>>();
+                Column::new(name.into(), values)
+            } else {
+                Column::new(name.into(), values)
+            }
+        })
+        .collect();
+    new_data_frame(columns)
+}
+
+// columns have the same length, so only duplicate names can fail
+fn new_data_frame(columns: Vec<Column>) -> Result<DataFrame, Error> {
+    DataFrame::new(columns).map_err(|_| Error::InvalidColumn {
+        name: "columns",
+        reason: "must have unique names",
+    })
+}
+
+fn fit_grouped<F>(
+    df: &DataFrame,
+    id_column: &str,
+    value_column: &str,
+    fit: F,
+) -> Result<DataFrame, Error>
+where
+    F: Fn(&Series) -> Result<DataFrame, Error>,
+{
+    let ids = df
+        .column(id_column)
+        .map_err(|_| Error::ColumnNotFound { name: "id_column" })?;
+    let values = df.column(value_column).map_err(|_| Error::ColumnNotFound {
+        name: "value_column",
+    })?;
+    let invalid_values = Error::InvalidColumn {
+        name: "value_column",
+        reason: "must be numeric",
+    };
+    if !values.dtype().is_primitive_numeric() {
+        return Err(invalid_values);
+    }
+    let values = values
+        .cast(&DataType::Float64)
+        .map_err(|_| invalid_values)?;
+    let values = values.f64().unwrap().iter().collect::<Vec<Option<f64>>>();
+
+    // group row indices by id, in order of first appearance
+    let keys = ids
+        .cast(&DataType::String)
+        .map_err(|_| Error::InvalidColumn {
+            name: "id_column",
+            reason: "must be castable to string",
+        })?;
+    let keys = keys.str().unwrap();
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut lookup = HashMap::new();
+    for (i, key) in keys.iter().enumerate() {
+        let key = key.ok_or(Error::InvalidColumn {
+            name: "id_column",
+            reason: "must not contain nulls",
+        })?;
+        let g = *lookup.entry(key).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[g].push(i);
+    }
+
+    let mut names = Vec::new();
+    let mut parts = Vec::with_capacity(groups.len());
+    for rows in &groups {
+        let group_values = rows.iter().map(|&i| values[i]).collect::<Vec<_>>();
+        let components = fit(&Series::new(value_column.into(), group_values))?;
+        if names.is_empty() {
+            names = components
+                .get_column_names()
+                .into_iter()
+                .map(|name| name.to_string())
+                .collect();
+            if names.iter().any(|name| name == id_column) {
+                return Err(Error::InvalidColumn {
+                    name: "id_column",
+                    reason: "must not have the same name as a component",
+                });
+            }
+        }
+        parts.push(components);
+    }
+
+    // scatter group results back to the original row order
+    let mut columns = vec![ids.clone()];
+    for name in &names {
+        let group_columns = parts
+            .iter()
+            .map(|p| p.column(name).unwrap().f64().unwrap())
+            .collect::<Vec<_>>();
+        let mut output = vec![None; df.height()];
+        for (g, rows) in groups.iter().enumerate() {
+            for (j, &i) in rows.iter().enumerate() {
+                output[i] = group_columns[g].get(j);
+            }
+        }
+        columns.push(Column::new(name.into(), output));
+    }
+    new_data_frame(columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stl::test_helpers::*;
+    use crate::{Error, Mstl, ParameterValue, Stl};
+    use polars::prelude::{Column, DataFrame, NamedFrom, Series};
+
+    fn generate_series() -> Vec<f64> {
+        vec![
+            5.0, 9.0, 2.0, 9.0, 0.0, 6.0, 3.0, 8.0, 5.0, 8.0, 7.0, 8.0, 8.0, 0.0, 2.0, 5.0, 0.0,
+            5.0, 6.0, 7.0, 3.0, 6.0, 1.0, 4.0, 4.0, 4.0, 3.0, 7.0, 5.0, 8.0,
+        ]
+    }
+
+    fn column(df: &DataFrame, name: &str) -> Vec<f64> {
+        df.column(name)
+            .unwrap()
+            .f64()
+            .unwrap()
+            .iter()
+            .map(|v| v.unwrap_or(f64::NAN))
+            .collect()
+    }
+
+    #[test]
+    fn test_stl() {
+        let series = Series::new("value".into(), generate_series());
+        let df = Stl::params().fit_polars(&series, 7).unwrap();
+        assert_eq!(
+            vec!["seasonal", "trend", "remainder", "weights"],
+            df.get_column_names_str()
+        );
+        assert_elements_in_delta(
+            &[0.36926576, 0.75655484, -1.3324139, 1.9553658, -0.6044802],
+            &column(&df, "seasonal")[..5],
+        );
+    }
+
+    #[test]
+    fn test_stl_nulls() {
+        let mut values = generate_series()
+            .into_iter()
+            .map(Some)
+            .collect::<Vec<Option<f64>>>();
+        values[3] = None;
+        let series = Series::new("value".into(), values);
+        let df = Stl::params().fit_polars(&series, 7).unwrap();
+        assert_eq!(1, df.column("remainder").unwrap().null_count());
+        assert_eq!(0, df.column("trend").unwrap().null_count());
+    }
+
+    #[test]
+    fn test_mstl() {
+        let series = Series::new("value".into(), generate_series());
+        let df = Mstl::params().fit_polars(&series, &[6, 10]).unwrap();
+        assert_eq!(
+            vec!["seasonal_6", "seasonal_10", "trend", "remainder"],
+            df.get_column_names_str()
+        );
+        assert_elements_in_delta(
+            &[5.139485, 5.223691, 5.3078976, 5.387292, 5.4666862],
+            &column(&df, "trend")[..5],
+        );
+    }
+
+    #[test]
+    fn test_fit_grouped() {
+        let mut ids = Vec::new();
+        let mut values = Vec::new();
+        for v in generate_series() {
+            ids.push(1i64);
+            values.push(v);
+            ids.push(2i64);
+            values.push(v * 2.0);
+        }
+        let df = DataFrame::new(vec![
+            Column::new("id".into(), ids),
+            Column::new("value".into(), values),
+        ])
+        .unwrap();
+
+        let result = Stl::params()
+            .fit_polars_grouped(&df, "id", "value", 7)
+            .unwrap();
+        assert_eq!(60, result.height());
+        let trend = column(&result, "trend");
+        let a = (0..5).map(|i| trend[2 * i]).collect::<Vec<f64>>();
+        let b = (0..5).map(|i| trend[2 * i + 1]).collect::<Vec<f64>>();
+        assert_elements_in_delta(&[4.804099, 4.9097075, 5.015316, 5.16045, 5.305584], &a);
+        assert_elements_in_delta(&[9.608198, 9.819415, 10.030632, 10.3209, 10.611168], &b);
+    }
+
+    #[test]
+    fn test_not_numeric() {
+        let series = Series::new("value".into(), vec!["a"; 30]);
+        let result = Stl::params().fit_polars(&series, 7);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::InvalidColumn {
+                name: "series",
+                reason: "must be numeric"
+            }
+        );
+    }
+
+    #[test]
+    fn test_mstl_duplicate_periods() {
+        let series = Series::new("value".into(), generate_series());
+        let result = Mstl::params().fit_polars(&series, &[7, 7]);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::InvalidParameter {
+                name: "periods",
+                value: ParameterValue::Integer(7),
+                reason: "must be unique"
+            }
+        );
+    }
+
+    #[test]
+    fn test_fit_grouped_id_column_name() {
+        let df = DataFrame::new(vec![
+            Column::new("trend".into(), vec![1i64; 30]),
+            Column::new("value".into(), generate_series()),
+        ])
+        .unwrap();
+        let result = Stl::params().fit_polars_grouped(&df, "trend", "value", 7);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::InvalidColumn {
+                name: "id_column",
+                reason: "must not have the same name as a component"
+            }
+        );
+    }
+}
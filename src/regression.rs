@@ -0,0 +1,70 @@
+use alloc::{vec, vec::Vec};
+
+use super::Error;
+
+// checks that each regressor has one finite value per observation
+pub(crate) fn check_regressors(regressors: &[Vec<f64>], n: usize) -> Result<(), Error> {
+    if let Some(r) = regressors.iter().find(|r| r.len() != n) {
+        return Err(Error::parameter(
+            "regressors",
+            r.len(),
+            "must have the same length as series",
+        ));
+    }
+    if let Some(&v) = regressors.iter().flatten().find(|v| !v.is_finite()) {
+        return Err(Error::float_parameter("regressors", v, "must be finite"));
+    }
+    Ok(())
+}
+
+// solves the normal equations by Gaussian elimination with partial pivoting
+pub(crate) fn least_squares(columns: &[Vec<f64>], y: &[f64]) -> Result<Vec<f64>, Error> {
+    let p = columns.len();
+    let mut a = vec![vec![0.0; p + 1]; p];
+    for i in 0..p {
+        for j in i..p {
+            let v = dot(&columns[i], &columns[j]);
+            a[i][j] = v;
+            a[j][i] = v;
+        }
+        a[i][p] = dot(&columns[i], y);
+    }
+
+    let scale = (0..p).map(|i| a[i][i]).fold(0.0, f64::max);
+    for k in 0..p {
+        let pivot = (k..p)
+            .max_by(|&i, &j| a[i][k].abs().total_cmp(&a[j][k].abs()))
+            .unwrap();
+        if a[pivot][k].abs() <= 1e-12 * scale.max(f64::MIN_POSITIVE) {
+            // the column is a combination of the previous columns
+            return Err(Error::parameter(
+                "regressors",
+                k,
+                "must be linearly independent",
+            ));
+        }
+        a.swap(k, pivot);
+        let (top, bottom) = a.split_at_mut(k + 1);
+        let row = &top[k];
+        for other in bottom {
+            let factor = other[k] / row[k];
+            for (x, y) in other[k..].iter_mut().zip(&row[k..]) {
+                *x -= factor * y;
+            }
+        }
+    }
+
+    let mut beta = vec![0.0; p];
+    for k in (0..p).rev() {
+        let mut v = a[k][p];
+        for j in k + 1..p {
+            v -= a[k][j] * beta[j];
+        }
+        beta[k] = v / a[k][k];
+    }
+    Ok(beta)
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
@@ -0,0 +1,722 @@
+// Wen, Q., Gao, J., Song, X., Sun, L., Xu, H., & Zhu, S. (2019).
+// RobustSTL: A Robust Seasonal-Trend Decomposition Algorithm for Long Time Series.
+// Proceedings of the AAAI Conference on Artificial Intelligence, 33(01), 5409-5416.
+//
+// The series is standardized by the noise level, denoised with a bilateral filter, and
+// seasonally differenced. The trend differences are estimated by least absolute
+// deviations with L1 penalties on the first and second differences of the trend, so the
+// trend can have abrupt level and slope changes. This is solved with consensus ADMM,
+// where each iteration is linear in the length of the series regardless of the period.
+// The seasonal component is a non-local filter of the detrended series over the same
+// phase in neighboring cycles. Unlike the paper, values are weighted by similarity to
+// the median over the same phase, rather than to the center of each neighborhood, so
+// spikes in neighboring cycles are left out.
+
+use alloc::{vec, vec::Vec};
+
+use super::stl_params::ensure_finite;
+use super::{Error, Float, StlResult};
+
+/// RobustSTL decomposition for series with abrupt trend changes.
+pub struct RobustStl;
+
+/// A set of RobustSTL parameters.
+#[derive(Clone, Debug)]
+pub struct RobustStlParams {
+    window: usize,
+    denoise_sigmas: (f64, f64),
+    trend_lambdas: (f64, f64),
+    seasonal_cycles: usize,
+    seasonal_sigmas: (f64, f64),
+}
+
+// maximum number of ADMM iterations and tolerances for the trend
+const TREND_ITERATIONS: usize = 5000;
+const TREND_ABS_TOL: f64 = 1e-4;
+const TREND_REL_TOL: f64 = 1e-3;
+const RELAXATION: f64 = 1.6;
+
+impl RobustStl {
+    /// Decomposes a time series.
+    pub fn fit<T: Float>(series: &[T], period: usize) -> Result<StlResult<T>, Error> {
+        RobustStlParams::new().fit(series, period)
+    }
+
+    /// Creates a new set of parameters.
+    pub fn params() -> RobustStlParams {
+        RobustStlParams::new()
+    }
+}
+
+impl RobustStlParams {
+    /// Creates a new set of parameters.
+    pub fn new() -> Self {
+        Self {
+            window: 3,
+            denoise_sigmas: (1.0, 1.0),
+            trend_lambdas: (1.0, 0.5),
+            seasonal_cycles: 2,
+            seasonal_sigmas: (50.0, 1.0),
+        }
+    }
+
+    /// Sets the number of neighbors on each side for denoising and seasonal filtering.
+    pub fn window(&mut self, window: usize) -> &mut Self {
+        self.window = window;
+        self
+    }
+
+    /// Sets the distance and value scales of the bilateral filter for denoising.
+    ///
+    /// Values are in units of the standard deviation of the noise.
+    pub fn denoise_sigmas(&mut self, distance: f64, value: f64) -> &mut Self {
+        self.denoise_sigmas = (distance, value);
+        self
+    }
+
+    /// Sets the penalties for changes in the level and the slope of the trend.
+    ///
+    /// The level penalty should be less than the period, or the trend will be constant.
+    pub fn trend_lambdas(&mut self, level: f64, slope: f64) -> &mut Self {
+        self.trend_lambdas = (level, slope);
+        self
+    }
+
+    /// Sets the number of cycles before and after each point for seasonal filtering.
+    pub fn seasonal_cycles(&mut self, cycles: usize) -> &mut Self {
+        self.seasonal_cycles = cycles;
+        self
+    }
+
+    /// Sets the distance and value scales of the non-local filter for the seasonal component.
+    ///
+    /// Values are in units of the standard deviation of the noise.
+    pub fn seasonal_sigmas(&mut self, distance: f64, value: f64) -> &mut Self {
+        self.seasonal_sigmas = (distance, value);
+        self
+    }
+
+    /// Decomposes a time series.
+    ///
+    /// The weights are all one.
+    pub fn fit<T: Float>(&self, series: &[T], period: usize) -> Result<StlResult<T>, Error> {
+        if period < 2 {
+            return Err(Error::parameter("period", period, "must be at least 2"));
+        }
+
+        let n = series.len();
+        if n / 2 < period {
+            return Err(Error::SeriesTooShort {
+                len: n,
+                min_len: 2 * period,
+            });
+        }
+
+        ensure_finite(series)?;
+
+        if self.seasonal_cycles == 0 {
+            return Err(Error::parameter("seasonal_cycles", 0, "must be at least 1"));
+        }
+        for (name, sigma) in [
+            ("denoise_sigmas", self.denoise_sigmas.0),
+            ("denoise_sigmas", self.denoise_sigmas.1),
+            ("seasonal_sigmas", self.seasonal_sigmas.0),
+            ("seasonal_sigmas", self.seasonal_sigmas.1),
+        ] {
+            if !(sigma > 0.0 && sigma.is_finite()) {
+                return Err(Error::float_parameter(name, sigma, "must be positive"));
+            }
+        }
+        for lambda in [self.trend_lambdas.0, self.trend_lambdas.1] {
+            if !(lambda >= 0.0 && lambda.is_finite()) {
+                return Err(Error::float_parameter(
+                    "trend_lambdas",
+                    lambda,
+                    "must be non-negative",
+                ));
+            }
+        }
+
+        // standardize by the noise level so the value scales do not depend on the units
+        let y = series.iter().map(|v| v.as_f64()).collect::<Vec<f64>>();
+        let mean = y.iter().sum::<f64>() / n as f64;
+        let scale = noise_scale(&y, period);
+        let y = y.iter().map(|v| (v - mean) / scale).collect::<Vec<f64>>();
+
+        let denoised = bilateral_filter(&y, self.window, self.denoise_sigmas);
+        let relative_trend =
+            extract_trend(&denoised, period, self.trend_lambdas, TREND_ITERATIONS)?;
+        let detrended = denoised
+            .iter()
+            .zip(&relative_trend)
+            .map(|(a, b)| a - b)
+            .collect::<Vec<f64>>();
+        let seasonal = seasonal_filter(
+            &detrended,
+            period,
+            self.window,
+            self.seasonal_cycles,
+            self.seasonal_sigmas,
+        );
+
+        // move the level of the seasonal component to the trend
+        let level = seasonal.iter().sum::<f64>() / n as f64;
+        let seasonal = seasonal
+            .iter()
+            .map(|s| T::from_f64((s - level) * scale))
+            .collect::<Vec<T>>();
+        let trend = relative_trend
+            .iter()
+            .map(|t| T::from_f64((t + level) * scale + mean))
+            .collect::<Vec<T>>();
+        let remainder = series
+            .iter()
+            .zip(&seasonal)
+            .zip(&trend)
+            .map(|((&v, &s), &t)| v - s - t)
+            .collect();
+
+        Ok(StlResult {
+            seasonal,
+            trend,
+            remainder,
+            weights: vec![T::one(); n],
+            period,
+        })
+    }
+}
+
+impl Default for RobustStlParams {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// a robust estimate of the standard deviation of the noise from the seasonal differences,
+// falling back to the standard deviation of the series
+fn noise_scale(y: &[f64], period: usize) -> f64 {
+    let mut diffs = (period..y.len())
+        .map(|t| y[t] - y[t - period])
+        .collect::<Vec<f64>>();
+    let center = median(&mut diffs);
+    let mut deviations = diffs
+        .iter()
+        .map(|v| (v - center).abs())
+        .collect::<Vec<f64>>();
+    let mad = median(&mut deviations);
+    if mad > 0.0 {
+        // the differences have twice the variance of the noise
+        return 1.4826 * mad / Float::sqrt(&2.0);
+    }
+
+    let n = y.len() as f64;
+    let mean = y.iter().sum::<f64>() / n;
+    let var = y.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / n;
+    if var > 0.0 {
+        Float::sqrt(&var)
+    } else {
+        1.0
+    }
+}
+
+fn median(values: &mut [f64]) -> f64 {
+    values.sort_unstable_by(|a, b| a.total_cmp(b));
+    let n = values.len();
+    (values[(n - 1) / 2] + values[n / 2]) / 2.0
+}
+
+fn gaussian(x: f64, sigma: f64) -> f64 {
+    (-x * x / (2.0 * sigma * sigma)).exp()
+}
+
+fn bilateral_filter(y: &[f64], window: usize, (distance, value): (f64, f64)) -> Vec<f64> {
+    let n = y.len();
+    (0..n)
+        .map(|t| {
+            let mut sum = 0.0;
+            let mut total = 0.0;
+            for j in t.saturating_sub(window)..(t + window + 1).min(n) {
+                let w = gaussian(j.abs_diff(t) as f64, distance) * gaussian(y[j] - y[t], value);
+                sum += w * y[j];
+                total += w;
+            }
+            sum / total
+        })
+        .collect()
+}
+
+// returns the trend relative to the first point
+fn extract_trend(
+    y: &[f64],
+    period: usize,
+    (level, slope): (f64, f64),
+    iterations: usize,
+) -> Result<Vec<f64>, Error> {
+    let n = y.len();
+
+    // minimize |S t - g| + level |D1 t| + slope |D2 t| over the trend t, where S takes seasonal
+    // differences and D1 and D2 take first and second differences. This is solved with ADMM on
+    // two copies of the trend that must agree, so each update only needs the seasonal
+    // differences (a tridiagonal system per phase) or the first and second differences
+    // (a pentadiagonal system), and each iteration is linear in the length of the series.
+    let g = (period..n)
+        .map(|t| y[t] - y[t - period])
+        .collect::<Vec<f64>>();
+    let inverse_pivots = seasonal_factor(n, period);
+    let mut bands = difference_bands(n);
+    banded_factor(&mut bands);
+
+    let mut a = vec![0.0; n];
+    let mut b = vec![0.0; n];
+    let mut z1 = vec![0.0; n - period];
+    let mut z2 = vec![0.0; n - 1];
+    let mut z3 = vec![0.0; n - 2];
+    let mut c = vec![0.0; n];
+    let mut u1 = vec![0.0; n - period];
+    let mut u2 = vec![0.0; n - 1];
+    let mut u3 = vec![0.0; n - 2];
+    let mut u4 = vec![0.0; n];
+    let mut u5 = vec![0.0; n];
+    let mut rhs = vec![0.0; n];
+    let mut dual = vec![0.0; n];
+    let mut v = vec![0.0; n];
+
+    // the penalty parameter is one, which suits the standardized series
+    let size = (5 * n - period - 3) as f64;
+    let mut converged = false;
+    for _ in 0..iterations {
+        // update the copy for the seasonal differences
+        for (r, ((g, z), u)) in g.iter().zip(&z1).zip(&u1).enumerate() {
+            v[r] = g + z - u;
+        }
+        rhs.fill(0.0);
+        seasonal_differences_adjoint(&v[..n - period], period, &mut rhs);
+        for ((r, c), u) in rhs.iter_mut().zip(&c).zip(&u4) {
+            *r += c - u;
+        }
+        a.copy_from_slice(&rhs);
+        seasonal_solve(&inverse_pivots, period, &mut a);
+
+        // update the copy for the first and second differences
+        for (r, (z, u)) in z2.iter().zip(&u2).enumerate() {
+            v[r] = z - u;
+        }
+        rhs.fill(0.0);
+        first_differences_adjoint(&v[..n - 1], &mut rhs);
+        for (r, (z, u)) in z3.iter().zip(&u3).enumerate() {
+            v[r] = z - u;
+        }
+        second_differences_adjoint(&v[..n - 2], &mut rhs);
+        for ((r, c), u) in rhs.iter_mut().zip(&c).zip(&u5) {
+            *r += c - u;
+        }
+        b.copy_from_slice(&rhs);
+        banded_solve(&bands, &mut b);
+
+        // update the splitting variables and the scaled dual variables with over-relaxation,
+        // tracking the primal residual and the change in the splitting variables
+        let mut primal = 0.0;
+        let mut primal_size = 0.0;
+        dual.fill(0.0);
+        for r in 0..n - period {
+            let w = a[r + period] - a[r] - g[r];
+            let relaxed = RELAXATION * w + (1.0 - RELAXATION) * z1[r];
+            let next = soft_threshold(relaxed + u1[r], 1.0);
+            let dz = next - z1[r];
+            dual[r + period] += dz;
+            dual[r] -= dz;
+            z1[r] = next;
+            u1[r] += relaxed - next;
+            primal += (w - next) * (w - next);
+            primal_size += w * w;
+        }
+        for i in 0..n - 1 {
+            let w = b[i + 1] - b[i];
+            let relaxed = RELAXATION * w + (1.0 - RELAXATION) * z2[i];
+            let next = soft_threshold(relaxed + u2[i], level);
+            let dz = next - z2[i];
+            dual[i + 1] += dz;
+            dual[i] -= dz;
+            z2[i] = next;
+            u2[i] += relaxed - next;
+            primal += (w - next) * (w - next);
+            primal_size += w * w;
+        }
+        for i in 0..n - 2 {
+            let w = b[i + 2] - 2.0 * b[i + 1] + b[i];
+            let relaxed = RELAXATION * w + (1.0 - RELAXATION) * z3[i];
+            let next = soft_threshold(relaxed + u3[i], slope);
+            let dz = next - z3[i];
+            dual[i + 2] += dz;
+            dual[i + 1] -= 2.0 * dz;
+            dual[i] += dz;
+            z3[i] = next;
+            u3[i] += relaxed - next;
+            primal += (w - next) * (w - next);
+            primal_size += w * w;
+        }
+        let mut dual_norm = 0.0;
+        let mut dual_size = 0.0;
+        for i in 0..n {
+            let ra = RELAXATION * a[i] + (1.0 - RELAXATION) * c[i];
+            let rb = RELAXATION * b[i] + (1.0 - RELAXATION) * c[i];
+            let next = (ra + u4[i] + rb + u5[i]) / 2.0;
+            let dc = next - c[i];
+            // the consensus variable enters the constraints for both copies
+            dual_norm += (dual[i] + dc) * (dual[i] + dc) + dc * dc;
+            c[i] = next;
+            u4[i] += ra - next;
+            u5[i] += rb - next;
+            primal += (a[i] - next) * (a[i] - next) + (b[i] - next) * (b[i] - next);
+            primal_size += a[i] * a[i] + b[i] * b[i];
+            dual_size += u4[i] * u4[i] + u5[i] * u5[i];
+        }
+        let primal = Float::sqrt(&primal);
+        let dual_norm = Float::sqrt(&dual_norm);
+
+        // stop when both residuals are small (Boyd et al., 2011, section 3.3)
+        let tolerance =
+            |norm: f64| TREND_ABS_TOL * Float::sqrt(&size) + TREND_REL_TOL * Float::sqrt(&norm);
+        if primal <= tolerance(primal_size) && dual_norm <= tolerance(dual_size) {
+            converged = true;
+            break;
+        }
+    }
+    if !converged {
+        return Err(Error::NotConverged { iterations });
+    }
+
+    let start = c[0];
+    Ok(c.iter().map(|v| v - start).collect())
+}
+
+fn soft_threshold(v: f64, t: f64) -> f64 {
+    if v > t {
+        v - t
+    } else if v < -t {
+        v + t
+    } else {
+        0.0
+    }
+}
+
+// adds S' v, where (S t)[r] = t[r + period] - t[r]
+fn seasonal_differences_adjoint(v: &[f64], period: usize, out: &mut [f64]) {
+    for (r, &w) in v.iter().enumerate() {
+        out[r + period] += w;
+        out[r] -= w;
+    }
+}
+
+fn first_differences_adjoint(v: &[f64], out: &mut [f64]) {
+    for (i, &w) in v.iter().enumerate() {
+        out[i + 1] += w;
+        out[i] -= w;
+    }
+}
+
+fn second_differences_adjoint(v: &[f64], out: &mut [f64]) {
+    for (i, &w) in v.iter().enumerate() {
+        out[i + 2] += w;
+        out[i + 1] -= 2.0 * w;
+        out[i] += w;
+    }
+}
+
+// the Thomas algorithm for S'S + I, which is tridiagonal with off-diagonal entries of -1
+// for each phase, returning the inverse pivots in the order of the series
+fn seasonal_factor(n: usize, period: usize) -> Vec<f64> {
+    let mut inverse_pivots = vec![0.0; n];
+    for t in 0..n {
+        let first = t < period;
+        let last = t + period >= n;
+        let diagonal = match (first, last) {
+            (true, true) => 1.0,
+            (true, false) | (false, true) => 2.0,
+            (false, false) => 3.0,
+        };
+        let pivot = if first {
+            diagonal
+        } else {
+            diagonal - inverse_pivots[t - period]
+        };
+        inverse_pivots[t] = 1.0 / pivot;
+    }
+    inverse_pivots
+}
+
+// solves (S'S + I) x = b in place, sweeping all phases at once
+fn seasonal_solve(inverse_pivots: &[f64], period: usize, b: &mut [f64]) {
+    let n = b.len();
+    for t in 0..n {
+        if t >= period {
+            b[t] += b[t - period];
+        }
+        b[t] *= inverse_pivots[t];
+    }
+    for t in (0..n.saturating_sub(period)).rev() {
+        b[t] += inverse_pivots[t] * b[t + period];
+    }
+}
+
+// the bands of D1'D1 + D2'D2 + I, where bands[d][i] is the entry (i, i - d)
+fn difference_bands(n: usize) -> Vec<Vec<f64>> {
+    let mut bands = vec![vec![0.0; n]; 3];
+    bands[0].fill(1.0);
+    for i in 0..n - 1 {
+        bands[0][i] += 1.0;
+        bands[0][i + 1] += 1.0;
+        bands[1][i + 1] -= 1.0;
+    }
+    for i in 0..n - 2 {
+        bands[0][i] += 1.0;
+        bands[0][i + 1] += 4.0;
+        bands[0][i + 2] += 1.0;
+        bands[1][i + 1] -= 2.0;
+        bands[1][i + 2] -= 2.0;
+        bands[2][i + 2] += 1.0;
+    }
+    bands
+}
+
+// the Cholesky factorization of a symmetric positive definite banded matrix in place,
+// where bands[d][i] is the entry (i, i - d)
+fn banded_factor(bands: &mut [Vec<f64>]) {
+    let n = bands[0].len();
+    let bandwidth = bands.len() - 1;
+    for i in 0..n {
+        for d in (1..=bandwidth.min(i)).rev() {
+            let j = i - d;
+            let mut v = bands[d][i];
+            for k in 1..=(bandwidth - d).min(j) {
+                v -= bands[d + k][i] * bands[k][j];
+            }
+            bands[d][i] = v / bands[0][j];
+        }
+        let v = bands[0][i]
+            - bands[1..=bandwidth.min(i)]
+                .iter()
+                .map(|band| band[i] * band[i])
+                .sum::<f64>();
+        bands[0][i] = Float::sqrt(&v);
+    }
+}
+
+fn banded_solve(bands: &[Vec<f64>], b: &mut [f64]) {
+    let n = b.len();
+    let bandwidth = bands.len() - 1;
+    for i in 0..n {
+        let mut v = b[i];
+        for d in 1..=bandwidth.min(i) {
+            v -= bands[d][i] * b[i - d];
+        }
+        b[i] = v / bands[0][i];
+    }
+    for i in (0..n).rev() {
+        let mut v = b[i];
+        for d in 1..=bandwidth.min(n - 1 - i) {
+            v -= bands[d][i + d] * b[i + d];
+        }
+        b[i] = v / bands[0][i];
+    }
+}
+
+fn seasonal_filter(
+    y: &[f64],
+    period: usize,
+    window: usize,
+    cycles: usize,
+    (distance, value): (f64, f64),
+) -> Vec<f64> {
+    let n = y.len();
+    let mut centers = Vec::with_capacity(2 * cycles);
+    let mut values = Vec::with_capacity(2 * cycles);
+    (0..n)
+        .map(|t| {
+            // the same phase in earlier and later cycles
+            centers.clear();
+            for k in 1..=cycles {
+                centers.extend(t.checked_sub(k * period));
+                centers.extend(Some(t + k * period).filter(|&c| c < n));
+            }
+            values.clear();
+            values.extend(centers.iter().map(|&c| y[c]));
+            let reference = median(&mut values);
+
+            let mut sum = 0.0;
+            let mut total = 0.0;
+            for &c in &centers {
+                let start = c.saturating_sub(window);
+                let end = (c + window + 1).min(n);
+                for (j, &v) in y[start..end].iter().enumerate() {
+                    let w = gaussian((start + j).abs_diff(c) as f64, distance)
+                        * gaussian(v - reference, value);
+                    sum += w * v;
+                    total += w;
+                }
+            }
+            if total > 0.0 {
+                sum / total
+            } else {
+                reference
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::extract_trend;
+    use crate::stl::test_helpers::*;
+    use crate::{Error, ParameterValue, RobustStl, Stl};
+    use alloc::{vec, vec::Vec};
+
+    fn generate_series() -> Vec<f32> {
+        vec![
+            5.0, 9.0, 2.0, 9.0, 0.0, 6.0, 3.0, 8.0, 5.0, 8.0, 7.0, 8.0, 8.0, 0.0, 2.0, 5.0, 0.0,
+            5.0, 6.0, 7.0, 3.0, 6.0, 1.0, 4.0, 4.0, 4.0, 3.0, 7.0, 5.0, 8.0,
+        ]
+    }
+
+    // a seasonal pattern with a level shift and a spike
+    fn generate_shift_series() -> Vec<f64> {
+        let pattern = [1.0, 3.0, -2.0, 0.5, 2.0, -1.5, -3.0];
+        let noise = [
+            0.1, -0.1, 0.05, -0.05, 0.0, 0.1, -0.1, 0.05, -0.05, 0.0, 0.1,
+        ];
+        (0..84)
+            .map(|i| {
+                let level = if i < 42 { 10.0 } else { 20.0 };
+                let spike = if i == 60 { 8.0 } else { 0.0 };
+                level + pattern[i % 7] + noise[i % 11] + spike
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_fit() {
+        let series = generate_series();
+        let result = RobustStl::fit(&series, 7).unwrap();
+        assert_eq!(30, result.seasonal().len());
+        for (i, &v) in series.iter().enumerate() {
+            assert_in_delta(
+                v,
+                result.seasonal()[i] + result.trend()[i] + result.remainder()[i],
+            );
+        }
+    }
+
+    #[test]
+    fn test_level_shift() {
+        let series = generate_shift_series();
+        let result = RobustStl::fit(&series, 7).unwrap();
+        let trend = result.trend();
+
+        // the shift stays abrupt
+        assert!((trend[38] - 10.0).abs() < 0.5, "{}", trend[38]);
+        assert!((trend[46] - 20.0).abs() < 0.5, "{}", trend[46]);
+
+        // the seasonal component is not affected by the shift
+        let pattern = [1.0, 3.0, -2.0, 0.5, 2.0, -1.5, -3.0];
+        for (i, &v) in result.seasonal().iter().enumerate() {
+            assert!((v - pattern[i % 7]).abs() < 0.3, "{} {}", i, v);
+        }
+
+        // the spike goes to the remainder
+        assert!(result.remainder()[60] > 6.0, "{}", result.remainder()[60]);
+
+        // compare to STL, which smears the shift
+        let stl = Stl::fit(&series, 7).unwrap();
+        assert!((stl.trend()[38] - 10.0).abs() > (trend[38] - 10.0).abs());
+    }
+
+    #[test]
+    fn test_weekly_hourly() {
+        // two months of hourly data with a weekly pattern and a level shift
+        let period = 168;
+        let pattern = |i: usize| {
+            let hour = (i % 24) as f64;
+            let day = (i % period / 24) as f64;
+            5.0 * (core::f64::consts::PI * hour / 12.0).sin() + if day >= 5.0 { 3.0 } else { 0.0 }
+        };
+        let mut state = 1u32;
+        let series = (0..12 * period)
+            .map(|i| {
+                state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+                let noise = (state >> 8) as f64 / (1 << 24) as f64 - 0.5;
+                let level = if i < 6 * period + 50 { 100.0 } else { 120.0 };
+                level + pattern(i) + 0.2 * noise
+            })
+            .collect::<Vec<f64>>();
+        let result = RobustStl::fit(&series, period).unwrap();
+
+        for (i, &v) in series.iter().enumerate() {
+            assert_in_delta(
+                v,
+                result.seasonal()[i] + result.trend()[i] + result.remainder()[i],
+            );
+        }
+
+        // the shift stays abrupt
+        let trend = result.trend();
+        let shift = 6 * period + 50;
+        assert!(
+            (trend[shift - 10] - 100.0).abs() < 1.0,
+            "{}",
+            trend[shift - 10]
+        );
+        assert!(
+            (trend[shift + 10] - 120.0).abs() < 1.0,
+            "{}",
+            trend[shift + 10]
+        );
+
+        // the seasonal component is not affected by the shift
+        let mean = (0..period).map(pattern).sum::<f64>() / period as f64;
+        for (i, &v) in result.seasonal().iter().enumerate() {
+            assert!((v - (pattern(i) - mean)).abs() < 1.0, "{} {}", i, v);
+        }
+    }
+
+    #[test]
+    fn test_constant() {
+        let series = vec![3.0; 20];
+        let result = RobustStl::fit(&series, 4).unwrap();
+        assert_elements_in_delta(&series, result.trend());
+        assert_elements_in_delta(&[0.0; 20], result.seasonal());
+    }
+
+    #[test]
+    fn test_too_short() {
+        let result = RobustStl::fit(&generate_series(), 16);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::SeriesTooShort {
+                len: 30,
+                min_len: 32
+            }
+        );
+    }
+
+    #[test]
+    fn test_bad_sigma() {
+        let result = RobustStl::params()
+            .denoise_sigmas(1.0, 0.0)
+            .fit(&generate_series(), 7);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::InvalidParameter {
+                name: "denoise_sigmas",
+                value: ParameterValue::Float(0.0),
+                reason: "must be positive",
+            }
+        );
+    }
+
+    #[test]
+    fn test_trend_not_converged() {
+        let series = generate_shift_series();
+        let result = extract_trend(&series, 7, (1.0, 0.5), 1);
+        assert_eq!(result.unwrap_err(), Error::NotConverged { iterations: 1 });
+    }
+}
@@ -0,0 +1,225 @@
+// SIMD versions of the slice kernels in Float, using portable SIMD (requires nightly).
+// Sums are accumulated across lanes, so results can differ from the scalar kernels by rounding.
+
+macro_rules! kernels {
+    ($t:ident, $lanes:literal) => {
+        pub(crate) mod $t {
+            use core::simd::prelude::*;
+
+            const LANES: usize = $lanes;
+            type V = Simd<$t, LANES>;
+
+            // offsets of the lanes
+            fn iota() -> V {
+                V::from_array(core::array::from_fn(|i| i as $t))
+            }
+
+            pub(crate) fn sum(x: &[$t]) -> $t {
+                let chunks = x.chunks_exact(LANES);
+                let rest = chunks.remainder().iter().sum::<$t>();
+                let mut acc = V::splat(0.0);
+                for c in chunks {
+                    acc += V::from_slice(c);
+                }
+                acc.reduce_sum() + rest
+            }
+
+            pub(crate) fn dot(x: &[$t], y: &[$t]) -> $t {
+                let n = x.len().min(y.len());
+                let (x, y) = (&x[..n], &y[..n]);
+                let split = n - n % LANES;
+                let mut acc = V::splat(0.0);
+                for (a, b) in x[..split]
+                    .chunks_exact(LANES)
+                    .zip(y[..split].chunks_exact(LANES))
+                {
+                    acc += V::from_slice(a) * V::from_slice(b);
+                }
+                let rest = x[split..]
+                    .iter()
+                    .zip(&y[split..])
+                    .map(|(a, b)| a * b)
+                    .sum::<$t>();
+                acc.reduce_sum() + rest
+            }
+
+            pub(crate) fn sub(out: &mut [$t], x: &[$t], y: &[$t]) {
+                let n = out.len().min(x.len()).min(y.len());
+                let split = n - n % LANES;
+                for ((o, a), b) in out[..split]
+                    .chunks_exact_mut(LANES)
+                    .zip(x[..split].chunks_exact(LANES))
+                    .zip(y[..split].chunks_exact(LANES))
+                {
+                    (V::from_slice(a) - V::from_slice(b)).copy_to_slice(o);
+                }
+                for i in split..n {
+                    out[i] = x[i] - y[i];
+                }
+            }
+
+            pub(crate) fn div(x: &mut [$t], d: $t) {
+                let mut chunks = x.chunks_exact_mut(LANES);
+                let dv = V::splat(d);
+                for c in &mut chunks {
+                    (V::from_slice(c) / dv).copy_to_slice(c);
+                }
+                for v in chunks.into_remainder() {
+                    *v /= d;
+                }
+            }
+
+            pub(crate) fn tricube_weights(w: &mut [$t], d0: $t, h: $t) {
+                let h9 = V::splat(0.999 * h);
+                let h1 = V::splat(0.001 * h);
+                let hv = V::splat(h);
+                let one = V::splat(1.0);
+                let mut d = V::splat(d0) + iota();
+                let step = V::splat(LANES as $t);
+                let mut chunks = w.chunks_exact_mut(LANES);
+                for c in &mut chunks {
+                    let r = d.abs();
+                    let u = r / hv;
+                    let t = one - u * u * u;
+                    let tricube = r.simd_le(h9).select(t * t * t, V::splat(0.0));
+                    r.simd_le(h1).select(one, tricube).copy_to_slice(c);
+                    d += step;
+                }
+                let rest = chunks.into_remainder();
+                let offset = d.to_array()[0];
+                crate::float::scalar::tricube_weights(rest, offset, h);
+            }
+
+            pub(crate) fn weighted_moments(
+                w: &[$t],
+                rw: Option<&[$t]>,
+                d0: $t,
+                y: &[$t],
+            ) -> [$t; 5] {
+                let n = w.len().min(y.len());
+                let split = n - n % LANES;
+                let step = V::splat(LANES as $t);
+                let mut d = V::splat(d0) + iota();
+                let mut sums = [V::splat(0.0); 5];
+                for i in (0..split).step_by(LANES) {
+                    let mut wv = V::from_slice(&w[i..]);
+                    if let Some(rw) = rw {
+                        wv *= V::from_slice(&rw[i..]);
+                    }
+                    let yv = V::from_slice(&y[i..]);
+                    let wd = wv * d;
+                    sums[0] += wv;
+                    sums[1] += wd;
+                    sums[2] += wd * d;
+                    sums[3] += wv * yv;
+                    sums[4] += wd * yv;
+                    d += step;
+                }
+                let rest = crate::float::scalar::weighted_moments(
+                    &w[split..n],
+                    rw.map(|rw| &rw[split..]),
+                    d0 + split as $t,
+                    &y[split..n],
+                );
+                core::array::from_fn(|i| sums[i].reduce_sum() + rest[i])
+            }
+        }
+    };
+}
+
+kernels!(f32, 8);
+kernels!(f64, 4);
+
+#[cfg(test)]
+#[cfg(feature = "alloc")]
+mod tests {
+    use crate::float::scalar;
+    use crate::stl::test_helpers::*;
+    use crate::Float;
+    use alloc::{vec, vec::Vec};
+
+    fn generate(n: usize, seed: u64) -> Vec<f64> {
+        let mut state = seed;
+        (0..n)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                (state >> 11) as f64 / (1u64 << 53) as f64 * 10.0 - 5.0
+            })
+            .collect()
+    }
+
+    // lengths around the lane counts to cover the remainders
+    const LENGTHS: [usize; 6] = [0, 1, 7, 8, 13, 101];
+
+    #[test]
+    fn test_sum_dot() {
+        for n in LENGTHS {
+            let x = generate(n, 1);
+            let y = generate(n, 2);
+            assert_in_delta(scalar::sum(&x), f64::slice_sum(&x));
+            assert_in_delta(scalar::dot(&x, &y), f64::slice_dot(&x, &y));
+
+            let x = x.iter().map(|&v| v as f32).collect::<Vec<f32>>();
+            let y = y.iter().map(|&v| v as f32).collect::<Vec<f32>>();
+            assert_in_delta(scalar::sum(&x), f32::slice_sum(&x));
+            assert_in_delta(scalar::dot(&x, &y), f32::slice_dot(&x, &y));
+        }
+    }
+
+    #[test]
+    fn test_sub_div() {
+        for n in LENGTHS {
+            let x = generate(n, 3);
+            let y = generate(n, 4);
+            let mut expected = vec![0.0; n];
+            let mut actual = vec![0.0; n];
+            scalar::sub(&mut expected, &x, &y);
+            f64::slice_sub(&mut actual, &x, &y);
+            assert_eq!(expected, actual);
+
+            scalar::div(&mut expected, 3.0);
+            f64::slice_div(&mut actual, 3.0);
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn test_tricube_weights() {
+        for n in LENGTHS {
+            let h = n as f64 / 2.0;
+            let mut expected = vec![0.0; n];
+            let mut actual = vec![0.0; n];
+            scalar::tricube_weights(&mut expected, -h.floor(), h);
+            f64::tricube_weights(&mut actual, -h.floor(), h);
+            assert_elements_in_delta(&expected, &actual);
+
+            let mut expected = vec![0.0; n];
+            let mut actual = vec![0.0; n];
+            scalar::tricube_weights(&mut expected, 3.0, h as f32 + 3.0);
+            f32::tricube_weights(&mut actual, 3.0, h as f32 + 3.0);
+            assert_elements_in_delta(&expected, &actual);
+        }
+    }
+
+    #[test]
+    fn test_weighted_moments() {
+        for n in LENGTHS {
+            let w = generate(n, 5).iter().map(|v| v.abs()).collect::<Vec<f64>>();
+            let rw = generate(n, 6)
+                .iter()
+                .map(|v| v.abs() / 5.0)
+                .collect::<Vec<f64>>();
+            let y = generate(n, 7);
+            let d0 = -(n as f64 / 2.0).floor();
+            for rw in [None, Some(&rw[..])] {
+                let expected = scalar::weighted_moments(&w, rw, d0, &y);
+                let actual = f64::weighted_moments(&w, rw, d0, &y);
+                for (e, a) in expected.iter().zip(&actual) {
+                    assert!((e - a).abs() <= 1e-9 * e.abs().max(1.0), "{} {}", e, a);
+                }
+            }
+        }
+    }
+}
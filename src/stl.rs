@@ -52,9 +52,10 @@ pub(crate) mod test_helpers {
 #[cfg(feature = "alloc")]
 mod tests {
     use crate::stl::test_helpers::*;
-    use crate::{Error, Stl};
-    use alloc::{vec, vec::Vec};
+    use crate::{Error, ParameterValue, SeasonalSmoother, Stl};
+    use alloc::{string::ToString, vec, vec::Vec};
 
+    #[allow(clippy::needless_return)]
     fn generate_series() -> Vec<f32> {
         return vec![
             5.0, 9.0, 2.0, 9.0, 0.0, 6.0, 3.0, 8.0, 5.0, 8.0, 7.0, 8.0, 8.0, 0.0, 2.0, 5.0, 0.0,
@@ -80,6 +81,32 @@ mod tests {
         assert_elements_in_delta(&[1.0, 1.0, 1.0, 1.0, 1.0], &result.weights()[..5]);
     }
 
+    #[test]
+    fn test_f32_long_windows() {
+        // long windows on a level well above the seasonal amplitude, where f32 rounding adds up,
+        // compared to f64
+        let series = (0..3000)
+            .map(|i| 100.0 + 0.01 * i as f64 + [1.0, 3.0, -2.0, 0.5, 2.0, -1.5, -3.0][i % 7])
+            .collect::<Vec<f64>>();
+        let mut params = Stl::params();
+        params
+            .seasonal_length(101)
+            .trend_length(1001)
+            .low_pass_length(1001)
+            .seasonal_jump(1)
+            .trend_jump(1)
+            .low_pass_jump(1);
+        let expected = params.fit(&series, 7).unwrap();
+        let series = series.iter().map(|&v| v as f32).collect::<Vec<f32>>();
+        let result = params.fit(&series, 7).unwrap();
+        for (&a, &b) in expected.seasonal().iter().zip(result.seasonal()) {
+            assert!((a - b as f64).abs() < 0.01, "{} {}", a, b);
+        }
+        for (&a, &b) in expected.trend().iter().zip(result.trend()) {
+            assert!((a - b as f64).abs() < 0.01, "{} {}", a, b);
+        }
+    }
+
     #[test]
     fn test_f64() {
         let series: Vec<f64> = vec![
@@ -150,26 +177,44 @@ mod tests {
         let result = Stl::fit(&generate_series(), 1);
         assert_eq!(
             result.unwrap_err(),
-            Error::Parameter("period must be at least 2")
+            Error::InvalidParameter {
+                name: "period",
+                value: ParameterValue::Integer(1),
+                reason: "must be at least 2",
+            }
         );
     }
 
     #[test]
     fn test_too_few_periods() {
         let result = Stl::params().fit(&generate_series(), 16);
+        let err = result.unwrap_err();
         assert_eq!(
-            result.unwrap_err(),
-            Error::Series("series has less than two periods")
+            err,
+            Error::SeriesTooShort {
+                len: 30,
+                min_len: 32
+            }
+        );
+        assert_eq!(
+            "series must have at least 32 values, got 30",
+            err.to_string()
         );
     }
 
     #[test]
     fn test_bad_seasonal_degree() {
         let result = Stl::params().seasonal_degree(2).fit(&generate_series(), 7);
+        let err = result.unwrap_err();
         assert_eq!(
-            result.unwrap_err(),
-            Error::Parameter("seasonal_degree must be 0 or 1")
+            err,
+            Error::InvalidParameter {
+                name: "seasonal_degree",
+                value: ParameterValue::Integer(2),
+                reason: "must be 0 or 1",
+            }
         );
+        assert_eq!("seasonal_degree must be 0 or 1, got 2", err.to_string());
     }
 
     #[test]
@@ -177,7 +222,11 @@ mod tests {
         let result = Stl::params().trend_degree(2).fit(&generate_series(), 7);
         assert_eq!(
             result.unwrap_err(),
-            Error::Parameter("trend_degree must be 0 or 1")
+            Error::InvalidParameter {
+                name: "trend_degree",
+                value: ParameterValue::Integer(2),
+                reason: "must be 0 or 1",
+            }
         );
     }
 
@@ -186,10 +235,334 @@ mod tests {
         let result = Stl::params().low_pass_degree(2).fit(&generate_series(), 7);
         assert_eq!(
             result.unwrap_err(),
-            Error::Parameter("low_pass_degree must be 0 or 1")
+            Error::InvalidParameter {
+                name: "low_pass_degree",
+                value: ParameterValue::Integer(2),
+                reason: "must be 0 or 1",
+            }
         );
     }
 
+    #[test]
+    fn test_bad_seasonal_length() {
+        let result = Stl::params().seasonal_length(2).fit(&generate_series(), 7);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::InvalidParameter {
+                name: "seasonal_length",
+                value: ParameterValue::Integer(2),
+                reason: "must be at least 3",
+            }
+        );
+    }
+
+    #[test]
+    fn test_bad_trend_length() {
+        let result = Stl::params().trend_length(1).fit(&generate_series(), 7);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::InvalidParameter {
+                name: "trend_length",
+                value: ParameterValue::Integer(1),
+                reason: "must be at least 3",
+            }
+        );
+    }
+
+    #[test]
+    fn test_bad_low_pass_length() {
+        let result = Stl::params().low_pass_length(2).fit(&generate_series(), 7);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::InvalidParameter {
+                name: "low_pass_length",
+                value: ParameterValue::Integer(2),
+                reason: "must be at least 3",
+            }
+        );
+    }
+
+    #[test]
+    fn test_even_low_pass_length() {
+        let even = Stl::params()
+            .low_pass_length(8)
+            .fit(&generate_series(), 7)
+            .unwrap();
+        let odd = Stl::params()
+            .low_pass_length(9)
+            .fit(&generate_series(), 7)
+            .unwrap();
+        assert_eq!(odd.trend(), even.trend());
+    }
+
+    #[test]
+    fn test_low_pass_length_less_than_period() {
+        let result = Stl::params().low_pass_length(5).fit(&generate_series(), 7);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_bad_seasonal_jump() {
+        let result = Stl::params().seasonal_jump(0).fit(&generate_series(), 7);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::InvalidParameter {
+                name: "seasonal_jump",
+                value: ParameterValue::Integer(0),
+                reason: "must be at least 1",
+            }
+        );
+    }
+
+    #[test]
+    fn test_bad_trend_jump() {
+        let result = Stl::params().trend_jump(0).fit(&generate_series(), 7);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::InvalidParameter {
+                name: "trend_jump",
+                value: ParameterValue::Integer(0),
+                reason: "must be at least 1",
+            }
+        );
+    }
+
+    #[test]
+    fn test_bad_low_pass_jump() {
+        let result = Stl::params().low_pass_jump(0).fit(&generate_series(), 7);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::InvalidParameter {
+                name: "low_pass_jump",
+                value: ParameterValue::Integer(0),
+                reason: "must be at least 1",
+            }
+        );
+    }
+
+    #[test]
+    fn test_bad_inner_loops() {
+        let result = Stl::params().inner_loops(0).fit(&generate_series(), 7);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::InvalidParameter {
+                name: "inner_loops",
+                value: ParameterValue::Integer(0),
+                reason: "must be at least 1",
+            }
+        );
+    }
+
+    #[test]
+    fn test_mean_smoother() {
+        let result = Stl::params()
+            .seasonal_smoother(SeasonalSmoother::Mean)
+            .fit(&generate_series(), 7)
+            .unwrap();
+        let seasonal = result.seasonal();
+        for i in 0..23 {
+            assert_in_delta(seasonal[i], seasonal[i + 7]);
+        }
+        assert_in_delta(0.0, seasonal[..7].iter().sum());
+    }
+
+    #[test]
+    fn test_median_smoother() {
+        let result = Stl::params()
+            .seasonal_smoother(SeasonalSmoother::Median)
+            .robust(true)
+            .fit(&generate_series(), 7)
+            .unwrap();
+        let seasonal = result.seasonal();
+        for i in 0..23 {
+            assert_in_delta(seasonal[i], seasonal[i + 7]);
+        }
+    }
+
+    #[test]
+    fn test_exponential_smoother() {
+        let series = generate_series();
+        let result = Stl::params()
+            .seasonal_smoother(SeasonalSmoother::Exponential(0.5))
+            .fit(&series, 7)
+            .unwrap();
+        let loess = Stl::fit(&series, 7).unwrap();
+        assert_ne!(loess.seasonal(), result.seasonal());
+        for (i, &v) in series.iter().enumerate() {
+            assert_in_delta(
+                v,
+                result.seasonal()[i] + result.trend()[i] + result.remainder()[i],
+            );
+        }
+    }
+
+    #[test]
+    fn test_bad_exponential_smoother() {
+        let result = Stl::params()
+            .seasonal_smoother(SeasonalSmoother::Exponential(0.0))
+            .fit(&generate_series(), 7);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::InvalidParameter {
+                name: "seasonal_smoother",
+                value: ParameterValue::Float(0.0),
+                reason: "smoothing factor must be greater than 0 and at most 1",
+            }
+        );
+    }
+
+    // long enough for the seasonal, trend, and low-pass windows to slide
+    fn generate_long_series() -> Vec<f64> {
+        let pattern = [1.0, 3.0, -2.0, 0.5, 2.0, -1.5, -3.0];
+        (0..210)
+            .map(|i| 5.0 + 0.03 * i as f64 + pattern[i % 7] + ((i * 37) % 11) as f64 / 4.0)
+            .collect()
+    }
+
+    #[test]
+    fn test_fast_loess() {
+        let series = generate_long_series();
+        let mut params = Stl::params();
+        params.seasonal_length(9).trend_degree(0);
+        let expected = params.fit(&series, 7).unwrap();
+        let result = params.fast_loess(true).fit(&series, 7).unwrap();
+        assert_elements_in_delta(expected.seasonal(), result.seasonal());
+        assert_elements_in_delta(expected.trend(), result.trend());
+        assert_elements_in_delta(expected.remainder(), result.remainder());
+    }
+
+    #[test]
+    fn test_fast_loess_long() {
+        // windows long enough to be updated incrementally, over many recomputations of the sums
+        let series = (0..5000)
+            .map(|i| {
+                let noise = ((i * 37) % 11) as f64 / 4.0;
+                100.0 + 0.01 * i as f64 + [1.0, 3.0, -2.0, 0.5, 2.0, -1.5, -3.0][i % 7] + noise
+            })
+            .collect::<Vec<f64>>();
+        let mut params = Stl::params();
+        params
+            .seasonal_length(301)
+            .trend_length(1001)
+            .low_pass_length(301)
+            .seasonal_jump(1)
+            .trend_jump(1)
+            .low_pass_jump(1);
+        let expected = params.fit(&series, 7).unwrap();
+        let result = params.fast_loess(true).fit(&series, 7).unwrap();
+        assert_elements_in_delta(expected.seasonal(), result.seasonal());
+        assert_elements_in_delta(expected.trend(), result.trend());
+        assert_elements_in_delta(expected.remainder(), result.remainder());
+
+        let series = series.iter().map(|&v| v as f32).collect::<Vec<f32>>();
+        let result = params.fit(&series, 7).unwrap();
+        for (&a, &b) in expected.trend().iter().zip(result.trend()) {
+            assert!((a - b as f64).abs() < 0.01, "{} {}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_fast_loess_robust() {
+        let series = generate_long_series();
+        let mut params = Stl::params();
+        params.seasonal_length(9).robust(true);
+        let expected = params.fit(&series, 7).unwrap();
+        let result = params.fast_loess(true).fit(&series, 7).unwrap();
+        assert_elements_in_delta(expected.seasonal(), result.seasonal());
+        assert_elements_in_delta(expected.trend(), result.trend());
+        assert_elements_in_delta(expected.weights(), result.weights());
+    }
+
+    #[test]
+    fn test_fast_loess_robust_long() {
+        // most windows are centered, with outliers so the robustness weights vary
+        let series = (0..2000)
+            .map(|i| {
+                let spike = if i % 97 == 0 { 20.0 } else { 0.0 };
+                5.0 + 0.003 * i as f64
+                    + [1.0, 3.0, -2.0, 0.5, 2.0, -1.5, -3.0][i % 7]
+                    + ((i * 37) % 11) as f64 / 4.0
+                    + spike
+            })
+            .collect::<Vec<f64>>();
+        let mut params = Stl::params();
+        params
+            .seasonal_length(15)
+            .trend_length(101)
+            .seasonal_jump(1)
+            .trend_jump(1)
+            .low_pass_jump(1)
+            .robust(true);
+        let expected = params.fit(&series, 7).unwrap();
+        let result = params.fast_loess(true).fit(&series, 7).unwrap();
+        assert_elements_in_delta(expected.seasonal(), result.seasonal());
+        assert_elements_in_delta(expected.trend(), result.trend());
+        assert_elements_in_delta(expected.remainder(), result.remainder());
+        assert_elements_in_delta(expected.weights(), result.weights());
+    }
+
+    #[test]
+    fn test_fast_loess_f32_long_windows() {
+        // the robust pass fits each window from the moments, which must stay accurate in f32
+        let series = (0..2000)
+            .map(|i| {
+                let spike = if i % 97 == 0 { 20.0 } else { 0.0 };
+                100.0 + 0.01 * i as f64 + [1.0, 3.0, -2.0, 0.5, 2.0, -1.5, -3.0][i % 7] + spike
+            })
+            .collect::<Vec<f64>>();
+        let mut params = Stl::params();
+        params
+            .seasonal_length(101)
+            .trend_length(501)
+            .low_pass_length(501)
+            .seasonal_jump(1)
+            .trend_jump(1)
+            .low_pass_jump(1)
+            .robust(true)
+            .outer_loops(3)
+            .fast_loess(true);
+        let expected = params.fit(&series, 7).unwrap();
+        let series = series.iter().map(|&v| v as f32).collect::<Vec<f32>>();
+        let result = params.fit(&series, 7).unwrap();
+        for (&a, &b) in expected.seasonal().iter().zip(result.seasonal()) {
+            assert!((a - b as f64).abs() < 0.01, "{} {}", a, b);
+        }
+        for (&a, &b) in expected.trend().iter().zip(result.trend()) {
+            assert!((a - b as f64).abs() < 0.01, "{} {}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_nan() {
+        let mut series = generate_series();
+        series[3] = f32::NAN;
+        let result = Stl::fit(&series, 7);
+        let err = result.unwrap_err();
+        assert_eq!(err, Error::NonFiniteValue { index: 3 });
+        assert_eq!("series has a non-finite value at index 3", err.to_string());
+    }
+
+    #[test]
+    fn test_infinite() {
+        let mut series = generate_series();
+        series[5] = f32::INFINITY;
+        series[8] = f32::NEG_INFINITY;
+        let result = Stl::fit(&series, 7);
+        assert_eq!(result.unwrap_err(), Error::NonFiniteValue { index: 5 });
+    }
+
+    #[test]
+    fn test_nan_unchecked() {
+        let mut series = generate_series();
+        series[3] = f32::NAN;
+        let result = Stl::params()
+            .robust(true)
+            .check_finite(false)
+            .fit(&series, 7)
+            .unwrap();
+        assert!(result.remainder()[3].is_nan());
+    }
+
     #[test]
     fn test_seasonal_strength() {
         let result = Stl::fit(&generate_series(), 7).unwrap();
@@ -215,6 +588,63 @@ mod tests {
         let result = Stl::fit(&series, 7).unwrap();
         assert_in_delta(1.0, result.trend_strength());
     }
+
+    #[test]
+    fn test_features() {
+        let result = Stl::fit(&generate_series(), 7).unwrap();
+        let features = result.features();
+        assert_in_delta(0.16384245231864702, features.trend_strength);
+        assert_in_delta(0.284111676315015, features.seasonal_strength);
+        assert_in_delta(0.031004605611629814, features.spikiness);
+        assert_in_delta(-0.8892288957725094, features.linearity);
+        assert_in_delta(1.333095707798218, features.curvature);
+        assert_eq!(3, features.seasonal_peak);
+        assert_eq!(6, features.seasonal_trough);
+        assert_in_delta(-0.2458500979710796, features.remainder_acf1);
+        assert_in_delta(0.8329567696092854, features.remainder_acf10);
+    }
+
+    #[test]
+    fn test_remainder_acf() {
+        let result = Stl::fit(&generate_series(), 7).unwrap();
+        assert_elements_in_delta(
+            &[
+                1.0,
+                -0.2458500979710796,
+                0.23611108196496286,
+                -0.2585441006936044,
+            ],
+            &result.remainder_acf(3),
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_ljung_box() {
+        let result = Stl::fit(&generate_series(), 7).unwrap();
+        let test = result.ljung_box(5);
+        assert_in_delta(15.021418832451033, test.statistic);
+        assert_in_delta(0.010271209552463651, test.p_value);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_box_pierce() {
+        let result = Stl::fit(&generate_series(), 7).unwrap();
+        let test = result.box_pierce(5);
+        assert_in_delta(12.313402725738086, test.statistic);
+        assert_in_delta(0.03073684756169407, test.p_value);
+    }
+
+    #[test]
+    fn test_remainder_stats() {
+        let result = Stl::fit(&generate_series(), 7).unwrap();
+        let stats = result.remainder_stats();
+        assert_in_delta(-0.03584455165196709, stats.mean);
+        assert_in_delta(4.653203776993508, stats.variance);
+        assert_in_delta(-0.2237641443026352, stats.skewness);
+        assert_in_delta(-0.8386546456751214, stats.kurtosis);
+    }
 }
 
 #[cfg(test)]
@@ -6,7 +6,7 @@
 
 #![allow(clippy::too_many_arguments)]
 
-use super::Float;
+use super::{Float, SeasonalSmoother};
 
 pub fn stl<T: Float>(
     y: &[T],
@@ -22,6 +22,8 @@ pub fn stl<T: Float>(
     nljump: usize,
     ni: usize,
     no: usize,
+    smoother: SeasonalSmoother,
+    fast: bool,
     rw: &mut [T],
     season: &mut [T],
     trend: &mut [T],
@@ -39,8 +41,8 @@ pub fn stl<T: Float>(
 
     loop {
         onestp(
-            y, n, np, ns, nt, nl, isdeg, itdeg, ildeg, nsjump, ntjump, nljump, ni, userw, rw,
-            season, trend, work1, work2, work3, work4, work5,
+            y, n, np, ns, nt, nl, isdeg, itdeg, ildeg, nsjump, ntjump, nljump, ni, smoother, fast,
+            userw, rw, season, trend, work1, work2, work3, work4, work5,
         );
         k += 1;
         if k > no {
@@ -66,6 +68,7 @@ fn ess<T: Float>(
     len: usize,
     ideg: i32,
     njump: usize,
+    fast: bool,
     userw: bool,
     rw: &[T],
     ys: &mut [T],
@@ -103,6 +106,8 @@ fn ess<T: Float>(
             }
             i += newnj;
         }
+    } else if newnj == 1 && fast {
+        ess_fast(y, n, len, ideg, userw, rw, ys, res);
     } else if newnj == 1 {
         // newnj equal to one, len less than n
         let nsh = len.div_ceil(2);
@@ -204,6 +209,295 @@ fn ess<T: Float>(
     }
 }
 
+// same as ess with a jump of one and len less than n, but for windows centered on the fitted
+// point, the tricube weights are computed once instead of for every point, and without
+// robustness weights, long windows are updated incrementally
+fn ess_fast<T: Float>(
+    y: &[T],
+    n: usize,
+    len: usize,
+    ideg: i32,
+    userw: bool,
+    rw: &[T],
+    ys: &mut [T],
+    res: &mut [T],
+) {
+    let nsh = len.div_ceil(2);
+    // the first and last fitted values with a centered window
+    let first = nsh + 1;
+    let last = n + nsh - len;
+
+    // windows at the ends are not centered
+    for i in (1..first).chain(last + 1..=n) {
+        let nleft = if i < first { 1 } else { n - len + 1 };
+        let ok = est(
+            y,
+            n,
+            len,
+            ideg,
+            T::from_usize(i),
+            &mut ys[i - 1],
+            nleft,
+            nleft + len - 1,
+            res,
+            userw,
+            rw,
+        );
+        if !ok {
+            ys[i - 1] = y[i - 1];
+        }
+    }
+    if first > last {
+        return;
+    }
+
+    // tricube weights by offset from the fitted point, which starts at nsh - 1
+    let range = T::from_usize(n) - T::one();
+    let h = T::from_usize(nsh - 1).max(T::from_usize(len - nsh));
+    let d0 = T::zero() - T::from_usize(nsh - 1);
+    let kernel = &mut res[..len];
+    T::tricube_weights(kernel, d0, h);
+    let offset = |j: usize| d0 + T::from_usize(j);
+    let linear = h > T::zero() && ideg > 0;
+
+    if !userw && len >= SLIDING_MIN_LEN {
+        ess_sliding(y, len, linear, range.as_f64(), first, last, ys);
+        return;
+    }
+
+    if !userw {
+        // the weights are the same for every centered window
+        let a = kernel.iter().copied().sum::<T>();
+        for k in kernel.iter_mut() {
+            *k /= a;
+        }
+        if linear {
+            let mut center = T::zero();
+            for (j, &k) in kernel.iter().enumerate() {
+                center += k * offset(j);
+            }
+            let mut c = T::zero();
+            for (j, &k) in kernel.iter().enumerate() {
+                c += k * pow2(offset(j) - center);
+            }
+            if c.sqrt() > T::from_f64(0.001) * range {
+                let b = (T::zero() - center) / c;
+                for (j, k) in kernel.iter_mut().enumerate() {
+                    *k *= b * (offset(j) - center) + T::one();
+                }
+            }
+        }
+        for i in first..=last {
+            let window = &y[i - nsh..i - nsh + len];
+            ys[i - 1] = T::slice_dot(kernel, window);
+        }
+        return;
+    }
+
+    // with robustness weights, the weighted least squares need a pass over each window
+    for i in first..=last {
+        let start = i - nsh;
+        let window = start..start + len;
+        ys[i - 1] = local_fit(
+            kernel,
+            Some(&rw[window.clone()]),
+            d0,
+            &y[window],
+            linear,
+            range,
+        )
+        .unwrap_or(y[i - 1]);
+    }
+}
+
+// the shortest window to update incrementally, below which a pass over the window is faster
+const SLIDING_MIN_LEN: usize = 300;
+
+// the number of sums of the values times powers of the scaled offset, for the tricube
+// weights times the offset
+const POWERS: usize = 11;
+
+// the tricube weights (1 - |u|^3)^3 as polynomials in the scaled offset u for u < 0 and u >= 0
+const LEFT_TRICUBE: [f64; 10] = [1.0, 0.0, 0.0, 3.0, 0.0, 0.0, 3.0, 0.0, 0.0, 1.0];
+const RIGHT_TRICUBE: [f64; 10] = [1.0, 0.0, 0.0, -3.0, 0.0, 0.0, 3.0, 0.0, 0.0, -1.0];
+
+// fits the centered windows without robustness weights in constant time per point, by keeping
+// the sums of the values times powers of the scaled offset on each side of the fitted point,
+// and moving them to the next point with the binomial expansion of (u - 1 / h)^m
+fn ess_sliding<T: Float>(
+    y: &[T],
+    len: usize,
+    linear: bool,
+    range: f64,
+    first: usize,
+    last: usize,
+    ys: &mut [T],
+) {
+    let nsh = len.div_ceil(2);
+    let h = (nsh - 1).max(len - nsh) as f64;
+    let d0 = -((nsh - 1) as f64);
+    let scaled = |j: usize| (d0 + j as f64) / h;
+    let weight = |u: f64| {
+        let coefficients = if u < 0.0 {
+            &LEFT_TRICUBE
+        } else {
+            &RIGHT_TRICUBE
+        };
+        coefficients.iter().rev().fold(0.0, |acc, c| acc * u + c)
+    };
+
+    // the weighted least squares for the centered window give the fitted value as a combination
+    // of the weighted sums of the values and of the values times the offset
+    let mut a = 0.0;
+    let mut center = 0.0;
+    for j in 0..len {
+        a += weight(scaled(j));
+        center += weight(scaled(j)) * (d0 + j as f64);
+    }
+    center /= a;
+    let mut c = 0.0;
+    for j in 0..len {
+        c += weight(scaled(j)) * (d0 + j as f64 - center) * (d0 + j as f64 - center);
+    }
+    c /= a;
+    let (constant, slope) = if linear && c.sqrt() > 0.001 * range {
+        let b = -center / c;
+        ((1.0 - b * center) / a, b * h / a)
+    } else {
+        (1.0 / a, 0.0)
+    };
+
+    // shift[m][k] is the coefficient of the sum for power k in the sum for power m after
+    // the offsets decrease by 1 / h
+    let mut step = [1.0; POWERS];
+    for e in 1..POWERS {
+        step[e] = step[e - 1] * (-1.0 / h);
+    }
+    let mut shift = [[0.0; POWERS]; POWERS];
+    for (m, row) in shift.iter_mut().enumerate() {
+        let mut binomial = 1.0;
+        for k in (0..=m).rev() {
+            row[k] = binomial * step[m - k];
+            binomial *= k as f64 / (m - k + 1) as f64;
+        }
+    }
+
+    let add = |sums: &mut [f64; POWERS], value: f64, u: f64, sign: f64| {
+        let mut term = sign * value;
+        for s in sums.iter_mut() {
+            *s += term;
+            term *= u;
+        }
+    };
+
+    let mut left = [0.0; POWERS];
+    let mut right = [0.0; POWERS];
+    // recompute the sums directly every h points so rounding errors do not build up
+    let refresh = (h as usize).max(1);
+    for i in first..=last {
+        let start = i - nsh;
+        if (i - first) % refresh == 0 {
+            left = [0.0; POWERS];
+            right = [0.0; POWERS];
+            for j in 0..len {
+                let u = scaled(j);
+                let sums = if u < 0.0 { &mut left } else { &mut right };
+                add(sums, y[start + j].as_f64(), u, 1.0);
+            }
+        } else {
+            for sums in [&mut left, &mut right] {
+                let previous = *sums;
+                for (m, (s, row)) in sums.iter_mut().zip(&shift).enumerate() {
+                    *s = row[..=m].iter().zip(&previous).map(|(r, p)| r * p).sum();
+                }
+            }
+            // the previous fitted point moves to the left side, the first point of the
+            // previous window leaves, and the last point of this window enters
+            let moved = y[i - 2].as_f64();
+            add(&mut right, moved, -1.0 / h, -1.0);
+            add(&mut left, moved, -1.0 / h, 1.0);
+            add(&mut left, y[start - 1].as_f64(), scaled(0) - 1.0 / h, -1.0);
+            add(
+                &mut right,
+                y[start + len - 1].as_f64(),
+                scaled(len - 1),
+                1.0,
+            );
+        }
+
+        let mut fit = 0.0;
+        for m in 0..LEFT_TRICUBE.len() {
+            fit += constant * (LEFT_TRICUBE[m] * left[m] + RIGHT_TRICUBE[m] * right[m]);
+            fit += slope * (LEFT_TRICUBE[m] * left[m + 1] + RIGHT_TRICUBE[m] * right[m + 1]);
+        }
+        ys[i - 1] = T::from_f64(fit);
+    }
+}
+
+// the weighted least squares fit at offset zero, with the spread about the weighted center
+// from a second pass like the scalar est, so the sums can use the moment kernels
+fn local_fit<T: Float>(
+    w: &[T],
+    rw: Option<&[T]>,
+    d0: T,
+    y: &[T],
+    linear: bool,
+    range: T,
+) -> Option<T> {
+    let [a, center, _, fit, _] = T::weighted_moments(w, rw, d0, y);
+    if a <= T::zero() {
+        return None;
+    }
+    let center = center / a;
+    let mut fit = fit / a;
+    if linear {
+        let [_, _, c, _, slope] = T::weighted_moments(w, rw, d0 - center, y);
+        let c = c / a;
+        if c.sqrt() > T::from_f64(0.001) * range {
+            fit += (T::zero() - center) / c * (slope / a);
+        }
+    }
+    Some(fit)
+}
+
+// same as the scalar version, but with the weighted sums from the moment kernels
+// so they can use SIMD
+#[cfg(feature = "simd")]
+fn est<T: Float>(
+    y: &[T],
+    n: usize,
+    len: usize,
+    ideg: i32,
+    xs: T,
+    ys: &mut T,
+    nleft: usize,
+    nright: usize,
+    w: &mut [T],
+    userw: bool,
+    rw: &[T],
+) -> bool {
+    let range = T::from_usize(n) - T::one();
+    let mut h = (xs - T::from_usize(nleft)).max(T::from_usize(nright) - xs);
+
+    if len > n {
+        h += T::from_usize((len - n) / 2);
+    }
+
+    let window = nleft - 1..nright;
+    let d0 = T::from_usize(nleft) - xs;
+    let w = &mut w[window.clone()];
+    T::tricube_weights(w, d0, h);
+    let rw = userw.then(|| &rw[window.clone()]);
+    match local_fit(w, rw, d0, &y[window], h > T::zero() && ideg > 0, range) {
+        Some(fit) => {
+            *ys = fit;
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(not(feature = "simd"))]
 fn est<T: Float>(
     y: &[T],
     n: usize,
@@ -291,21 +585,24 @@ fn fts<T: Float>(x: &[T], n: usize, np: usize, trend: &mut [T], work: &mut [T])
     ma(work, n - 2 * np + 2, 3, trend);
 }
 
-fn ma<T: Float>(x: &[T], n: usize, len: usize, ave: &mut [T]) {
+pub(crate) fn ma<T: Float>(x: &[T], n: usize, len: usize, ave: &mut [T]) {
     let newn = n - len + 1;
     let flen = T::from_usize(len);
 
-    // get the first average
-    let mut v: T = x.iter().take(len).copied().sum();
-    ave[0] = v / flen;
+    // get the first sum
+    let mut v = T::slice_sum(&x[..len]);
+    ave[0] = v;
 
     if newn > 1 {
         for (m, aj) in ave.iter_mut().take(newn).skip(1).enumerate() {
             // window down the array
             v = v - x[m] + x[len + m];
-            *aj = v / flen;
+            *aj = v;
         }
     }
+
+    // sums to averages
+    T::slice_div(&mut ave[..newn], flen);
 }
 
 fn onestp<T: Float>(
@@ -322,6 +619,8 @@ fn onestp<T: Float>(
     ntjump: usize,
     nljump: usize,
     ni: usize,
+    smoother: SeasonalSmoother,
+    fast: bool,
     userw: bool,
     rw: &mut [T],
     season: &mut [T],
@@ -333,22 +632,19 @@ fn onestp<T: Float>(
     work5: &mut [T],
 ) {
     for _ in 0..ni {
-        for i in 0..n {
-            work1[i] = y[i] - trend[i];
-        }
+        T::slice_sub(&mut work1[..n], y, &trend[..n]);
 
         ss(
-            work1, n, np, ns, isdeg, nsjump, userw, rw, work2, work3, work4, work5, season,
+            work1, n, np, ns, isdeg, nsjump, smoother, fast, userw, rw, work2, work3, work4, work5,
+            season,
         );
         fts(work2, n + 2 * np, np, work3, work1);
-        ess(work3, n, nl, ildeg, nljump, false, work4, work1, work5);
-        for i in 0..n {
-            season[i] = work2[np + i] - work1[i];
-        }
-        for i in 0..n {
-            work1[i] = y[i] - season[i];
-        }
-        ess(work1, n, nt, itdeg, ntjump, userw, rw, trend, work3);
+        ess(
+            work3, n, nl, ildeg, nljump, fast, false, work4, work1, work5,
+        );
+        T::slice_sub(&mut season[..n], &work2[np..np + n], &work1[..n]);
+        T::slice_sub(&mut work1[..n], y, &season[..n]);
+        ess(work1, n, nt, itdeg, ntjump, fast, userw, rw, trend, work3);
     }
 }
 
@@ -360,7 +656,9 @@ fn rwts<T: Float>(y: &[T], n: usize, fit: &[T], rw: &mut [T]) {
     let mid1 = (n - 1) / 2;
     let mid2 = n / 2;
 
-    rw.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap());
+    // sort NaN last so unchecked non-finite input does not panic
+    #[allow(clippy::eq_op)]
+    rw.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap_or_else(|| (a != a).cmp(&(b != b))));
 
     let cmad = T::from_f64(3.0) * (rw[mid1] + rw[mid2]); // 6 * median abs resid
     let c9 = T::from_f64(0.999) * cmad;
@@ -385,6 +683,8 @@ fn ss<T: Float>(
     ns: usize,
     isdeg: i32,
     nsjump: usize,
+    smoother: SeasonalSmoother,
+    fast: bool,
     userw: bool,
     rw: &[T],
     season: &mut [T],
@@ -404,52 +704,68 @@ fn ss<T: Float>(
                 work3[i - 1] = rw[(i - 1) * np + j - 1];
             }
         }
-        ess(
-            work1,
-            k,
-            ns,
-            isdeg,
-            nsjump,
-            userw,
-            work3,
-            &mut work2[1..],
-            work4,
-        );
-        let mut xs = T::zero();
-        let nright = ns.min(k);
-        let ok = est(
-            work1,
-            k,
-            ns,
-            isdeg,
-            xs,
-            &mut work2[0],
-            1,
-            nright,
-            work4,
-            userw,
-            work3,
-        );
-        if !ok {
-            work2[0] = work2[1];
-        }
-        xs = T::from_usize(k + 1);
-        let nleft = 1.max(k as i32 - ns as i32 + 1) as usize;
-        let ok = est(
-            work1,
-            k,
-            ns,
-            isdeg,
-            xs,
-            &mut work2[k + 1],
-            nleft,
-            k,
-            work4,
-            userw,
-            work3,
-        );
-        if !ok {
-            work2[k + 1] = work2[k];
+        match smoother {
+            SeasonalSmoother::Loess => {
+                ess(
+                    work1,
+                    k,
+                    ns,
+                    isdeg,
+                    nsjump,
+                    fast,
+                    userw,
+                    work3,
+                    &mut work2[1..],
+                    work4,
+                );
+                let mut xs = T::zero();
+                let nright = ns.min(k);
+                let ok = est(
+                    work1,
+                    k,
+                    ns,
+                    isdeg,
+                    xs,
+                    &mut work2[0],
+                    1,
+                    nright,
+                    work4,
+                    userw,
+                    work3,
+                );
+                if !ok {
+                    work2[0] = work2[1];
+                }
+                xs = T::from_usize(k + 1);
+                let nleft = 1.max(k as i32 - ns as i32 + 1) as usize;
+                let ok = est(
+                    work1,
+                    k,
+                    ns,
+                    isdeg,
+                    xs,
+                    &mut work2[k + 1],
+                    nleft,
+                    k,
+                    work4,
+                    userw,
+                    work3,
+                );
+                if !ok {
+                    work2[k + 1] = work2[k];
+                }
+            }
+            SeasonalSmoother::Mean => {
+                let v = mean(work1, k, userw, work3);
+                work2[..k + 2].fill(v);
+            }
+            SeasonalSmoother::Median => {
+                let v = median(work1, k, userw, work3, work4);
+                work2[..k + 2].fill(v);
+            }
+            SeasonalSmoother::Exponential(alpha) => {
+                exponential(work1, k, T::from_f64(alpha as f64), userw, work3, work2);
+            }
         }
         for m in 1..=k + 2 {
             season[(m - 1) * np + j - 1] = work2[m - 1];
@@ -457,10 +773,59 @@ fn ss<T: Float>(
     }
 }
 
+// weighted mean of a cycle-subseries
+fn mean<T: Float>(y: &[T], k: usize, userw: bool, rw: &[T]) -> T {
+    if userw {
+        let mut sum = T::zero();
+        let mut weight = T::zero();
+        for i in 0..k {
+            sum += rw[i] * y[i];
+            weight += rw[i];
+        }
+        if weight > T::zero() {
+            return sum / weight;
+        }
+    }
+    y[..k].iter().copied().sum::<T>() / T::from_usize(k)
+}
+
+// median of a cycle-subseries, skipping values with zero robustness weight
+fn median<T: Float>(y: &[T], k: usize, userw: bool, rw: &[T], work: &mut [T]) -> T {
+    let mut m = 0;
+    for i in 0..k {
+        if !userw || rw[i] > T::zero() {
+            work[m] = y[i];
+            m += 1;
+        }
+    }
+    if m == 0 {
+        work[..k].copy_from_slice(&y[..k]);
+        m = k;
+    }
+    let work = &mut work[..m];
+    work.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap_or(core::cmp::Ordering::Equal));
+    (work[(m - 1) / 2] + work[m / 2]) / T::from_f64(2.0)
+}
+
+// simple exponential smoothing across cycles, with the ends extended by one cycle
+fn exponential<T: Float>(y: &[T], k: usize, alpha: T, userw: bool, rw: &[T], ys: &mut [T]) {
+    let mut level = y[0];
+    for i in 0..k {
+        let a = if userw { alpha * rw[i] } else { alpha };
+        if i > 0 {
+            level += a * (y[i] - level);
+        }
+        ys[i + 1] = level;
+    }
+    ys[0] = ys[1];
+    ys[k + 1] = ys[k];
+}
+
 fn pow2<T: Float>(x: T) -> T {
     x * x
 }
 
+#[cfg(not(feature = "simd"))]
 fn pow3<T: Float>(x: T) -> T {
     x * x * x
 }
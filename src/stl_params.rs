@@ -15,6 +15,20 @@ fn ceil(x: f32) -> f32 {
 #[cfg(not(feature = "std"))]
 use core::f32::math::ceil;
 
+/// A smoother for the cycle-subseries.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum SeasonalSmoother {
+    /// Loess with the seasonal length, degree, and jump.
+    #[default]
+    Loess,
+    /// The mean of each cycle-subseries, for a constant seasonal pattern.
+    Mean,
+    /// The median of each cycle-subseries.
+    Median,
+    /// Simple exponential smoothing across cycles with the given smoothing factor.
+    Exponential(f32),
+}
+
 /// A set of STL parameters.
 #[derive(Clone, Debug)]
 pub struct StlParams {
@@ -30,6 +44,11 @@ pub struct StlParams {
     ni: Option<usize>,
     no: Option<usize>,
     robust: bool,
+    smoother: SeasonalSmoother,
+    fast_loess: bool,
+    check_finite: bool,
+    #[cfg(feature = "alloc")]
+    pub(crate) extension: usize,
 }
 
 impl StlParams {
@@ -48,22 +67,33 @@ impl StlParams {
             ni: None,
             no: None,
             robust: false,
+            smoother: SeasonalSmoother::Loess,
+            fast_loess: false,
+            check_finite: true,
+            #[cfg(feature = "alloc")]
+            extension: 0,
         }
     }
 
     /// Sets the length of the seasonal smoother.
+    ///
+    /// Even lengths are rounded up to the next odd number.
     pub fn seasonal_length(&mut self, length: usize) -> &mut Self {
         self.ns = Some(length);
         self
     }
 
     /// Sets the length of the trend smoother.
+    ///
+    /// Even lengths are rounded up to the next odd number.
     pub fn trend_length(&mut self, length: usize) -> &mut Self {
         self.nt = Some(length);
         self
     }
 
     /// Sets the length of the low-pass filter.
+    ///
+    /// Even lengths are rounded up to the next odd number.
     pub fn low_pass_length(&mut self, length: usize) -> &mut Self {
         self.nl = Some(length);
         self
@@ -123,15 +153,65 @@ impl StlParams {
         self
     }
 
+    /// Sets the smoother for the cycle-subseries.
+    pub fn seasonal_smoother(&mut self, smoother: SeasonalSmoother) -> &mut Self {
+        self.smoother = smoother;
+        self
+    }
+
+    /// Sets whether to use a faster loess when the jump is 1.
+    ///
+    /// Without robustness weights, long smoothers update sums as the window slides, so each
+    /// fitted value takes constant time instead of time proportional to the length. With
+    /// robustness weights, the tricube weights are computed once per pass. This matches the
+    /// default up to rounding.
+    pub fn fast_loess(&mut self, fast_loess: bool) -> &mut Self {
+        self.fast_loess = fast_loess;
+        self
+    }
+
+    /// Sets whether to check the series for non-finite values (enabled by default).
+    pub fn check_finite(&mut self, check_finite: bool) -> &mut Self {
+        self.check_finite = check_finite;
+        self
+    }
+
+    /// Sets the number of forecasts and backcasts to extend the series with before decomposing.
+    #[cfg(feature = "alloc")]
+    pub fn extension(&mut self, horizon: usize) -> &mut Self {
+        self.extension = horizon;
+        self
+    }
+
     /// Decomposes a time series.
     #[cfg(feature = "alloc")]
     pub fn fit<T: Float>(&self, series: &[T], period: usize) -> Result<StlResult<T>, Error> {
+        self.fit_projected(series, period).map(|(result, _)| result)
+    }
+
+    // also returns the seasonal component projected one cycle past the end
+    #[cfg(feature = "alloc")]
+    pub(crate) fn fit_projected<T: Float>(
+        &self,
+        series: &[T],
+        period: usize,
+    ) -> Result<(StlResult<T>, Vec<T>), Error> {
+        if self.extension > 0 {
+            return self.fit_extended(series, period);
+        }
+
         let n = series.len();
         let np = period;
 
         // check before allocating
         if n / 2 < np {
-            return Err(Error::Series("series has less than two periods"));
+            return Err(Error::SeriesTooShort {
+                len: n,
+                min_len: 2 * np,
+            });
+        }
+        if self.check_finite {
+            ensure_finite(series)?;
         }
         let np = np.max(2);
 
@@ -154,12 +234,18 @@ impl StlParams {
             remainder.push(series[i] - seasonal[i] - trend[i]);
         }
 
-        Ok(StlResult {
-            seasonal,
-            trend,
-            remainder,
-            weights,
-        })
+        let projected = projection(&seasonal, period, &work);
+
+        Ok((
+            StlResult {
+                seasonal,
+                trend,
+                remainder,
+                weights,
+                period,
+            },
+            projected,
+        ))
     }
 
     /// Decomposes a time series with zero allocations.
@@ -177,7 +263,13 @@ impl StlParams {
         let np = period;
 
         if n / 2 < np {
-            return Err(Error::Series("series has less than two periods"));
+            return Err(Error::SeriesTooShort {
+                len: n,
+                min_len: 2 * np,
+            });
+        }
+        if self.check_finite {
+            ensure_finite(series)?;
         }
         let np = np.max(2);
 
@@ -199,7 +291,7 @@ impl StlParams {
         work: &mut [T],
     ) -> Result<(), Error> {
         if period < 2 {
-            return Err(Error::Parameter("period must be at least 2"));
+            return Err(Error::parameter("period", period, "must be at least 2"));
         }
 
         let newnp = period;
@@ -209,30 +301,74 @@ impl StlParams {
         let ildeg = self.ildeg.unwrap_or(itdeg);
 
         if isdeg != 0 && isdeg != 1 {
-            return Err(Error::Parameter("seasonal_degree must be 0 or 1"));
+            return Err(Error::degree("seasonal_degree", isdeg));
         }
         if itdeg != 0 && itdeg != 1 {
-            return Err(Error::Parameter("trend_degree must be 0 or 1"));
+            return Err(Error::degree("trend_degree", itdeg));
         }
         if ildeg != 0 && ildeg != 1 {
-            return Err(Error::Parameter("low_pass_degree must be 0 or 1"));
+            return Err(Error::degree("low_pass_degree", ildeg));
         }
 
-        let mut newns = self.ns.unwrap_or(newnp).max(3);
+        if let Some(ns) = self.ns {
+            if ns < 3 {
+                return Err(Error::parameter(
+                    "seasonal_length",
+                    ns,
+                    "must be at least 3",
+                ));
+            }
+        }
+        if let Some(nt) = self.nt {
+            if nt < 3 {
+                return Err(Error::parameter("trend_length", nt, "must be at least 3"));
+            }
+        }
+        if let Some(nl) = self.nl {
+            if nl < 3 {
+                return Err(Error::parameter(
+                    "low_pass_length",
+                    nl,
+                    "must be at least 3",
+                ));
+            }
+        }
+        for (name, jump) in [
+            ("seasonal_jump", self.nsjump),
+            ("trend_jump", self.ntjump),
+            ("low_pass_jump", self.nljump),
+        ] {
+            if jump == Some(0) {
+                return Err(Error::parameter(name, 0, "must be at least 1"));
+            }
+        }
+        if self.ni == Some(0) {
+            return Err(Error::parameter("inner_loops", 0, "must be at least 1"));
+        }
+        if let SeasonalSmoother::Exponential(alpha) = self.smoother {
+            if !(alpha > 0.0 && alpha <= 1.0) {
+                return Err(Error::float_parameter(
+                    "seasonal_smoother",
+                    alpha as f64,
+                    "smoothing factor must be greater than 0 and at most 1",
+                ));
+            }
+        }
+
+        let mut newns = self.ns.unwrap_or(newnp);
         if newns % 2 == 0 {
             newns += 1;
         }
 
         let mut nt = self
             .nt
-            .unwrap_or_else(|| ceil((1.5 * newnp as f32) / (1.0 - 1.5 / newns as f32)) as usize)
-            .max(3);
+            .unwrap_or_else(|| ceil((1.5 * newnp as f32) / (1.0 - 1.5 / newns as f32)) as usize);
         if nt % 2 == 0 {
             nt += 1;
         }
 
-        let mut nl = self.nl.unwrap_or(newnp).max(3);
-        if nl % 2 == 0 && self.nl.is_none() {
+        let mut nl = self.nl.unwrap_or(newnp);
+        if nl % 2 == 0 {
             nl += 1;
         }
 
@@ -260,14 +396,52 @@ impl StlParams {
         trend.fill(T::zero());
 
         stl(
-            series, newnp, newns, nt, nl, isdeg, itdeg, ildeg, nsjump, ntjump, nljump, ni, no,
-            weights, seasonal, trend, work,
+            series,
+            newnp,
+            newns,
+            nt,
+            nl,
+            isdeg,
+            itdeg,
+            ildeg,
+            nsjump,
+            ntjump,
+            nljump,
+            ni,
+            no,
+            self.smoother,
+            self.fast_loess,
+            weights,
+            seasonal,
+            trend,
+            work,
         );
 
         Ok(())
     }
 }
 
+// the cycle-subseries smoothing extends each subseries by one value past the end,
+// so adjust it by the last value of the low-pass component
+#[cfg(feature = "alloc")]
+pub(crate) fn projection<T: Float>(seasonal: &[T], period: usize, work: &[T]) -> Vec<T> {
+    let n = seasonal.len();
+    let np = period;
+    let extended = &work[n + 2 * np..];
+    let low_pass = extended[np + n - 1] - seasonal[n - 1];
+    extended[np + n..2 * np + n]
+        .iter()
+        .map(|&v| v - low_pass)
+        .collect()
+}
+
+pub(crate) fn ensure_finite<T: Float>(series: &[T]) -> Result<(), Error> {
+    match series.iter().position(|v| !v.is_finite()) {
+        Some(index) => Err(Error::NonFiniteValue { index }),
+        None => Ok(()),
+    }
+}
+
 impl Default for StlParams {
     fn default() -> Self {
         Self::new()
@@ -1,6 +1,12 @@
 use alloc::vec::Vec;
 
-use super::Float;
+use super::changepoint::{ChangepointParams, ChangepointResult};
+#[cfg(feature = "std")]
+use super::diagnostics::PortmanteauTest;
+use super::diagnostics::{self, RemainderStats};
+use super::features::{self, StlFeatures};
+use super::pattern::SeasonalPattern;
+use super::{Error, Float};
 
 /// A STL result.
 #[derive(Clone, Debug)]
@@ -9,9 +15,10 @@ pub struct StlResult<T: Float = f32> {
     pub(crate) trend: Vec<T>,
     pub(crate) remainder: Vec<T>,
     pub(crate) weights: Vec<T>,
+    pub(crate) period: usize,
 }
 
-fn var<T: Float>(series: &[T]) -> f64 {
+pub(crate) fn var<T: Float>(series: &[T]) -> f64 {
     let mean = series.iter().map(|v| (*v).as_f64()).sum::<f64>() / series.len() as f64;
     series
         .iter()
@@ -23,13 +30,15 @@ fn var<T: Float>(series: &[T]) -> f64 {
         / (series.len() as f64 - 1.0)
 }
 
+// skips NaN values, like the ends of a classical decomposition
 pub(crate) fn strength<T: Float>(component: &[T], remainder: &[T]) -> f64 {
-    let sr = component
+    let (r, sr): (Vec<T>, Vec<T>) = component
         .iter()
         .zip(remainder)
-        .map(|(a, b)| *a + *b)
-        .collect::<Vec<T>>();
-    (1.0 - var(remainder) / var(&sr)).max(0.0)
+        .filter(|(a, b)| !a.as_f64().is_nan() && !b.as_f64().is_nan())
+        .map(|(a, b)| (*b, *a + *b))
+        .unzip();
+    (1.0 - var(&r) / var(&sr)).max(0.0)
 }
 
 impl<T: Float> StlResult<T> {
@@ -63,6 +72,67 @@ impl<T: Float> StlResult<T> {
         strength(self.trend(), self.remainder())
     }
 
+    /// Returns STL-based features.
+    ///
+    /// Features of the trend and remainder use the span where both are not NaN.
+    pub fn features(&self) -> StlFeatures {
+        let span = features::finite_span(self.trend(), self.remainder());
+        let trend = &self.trend()[span.clone()];
+        let remainder = &self.remainder()[span];
+        let (linearity, curvature) = features::linearity_curvature(trend);
+        let (seasonal_peak, seasonal_trough) = features::peak_trough(self.seasonal(), self.period);
+        let (remainder_acf1, remainder_acf10) = features::remainder_acf(remainder);
+        StlFeatures {
+            trend_strength: self.trend_strength(),
+            seasonal_strength: self.seasonal_strength(),
+            spikiness: features::spikiness(remainder),
+            linearity,
+            curvature,
+            seasonal_peak,
+            seasonal_trough,
+            remainder_acf1,
+            remainder_acf10,
+        }
+    }
+
+    /// Detects changepoints in the seasonally adjusted series.
+    pub fn changepoints(&self, params: &ChangepointParams) -> Result<ChangepointResult, Error> {
+        let adjusted = self
+            .trend()
+            .iter()
+            .zip(self.remainder())
+            .map(|(t, r)| *t + *r)
+            .collect::<Vec<T>>();
+        params.fit(&adjusted)
+    }
+
+    /// Returns the autocorrelation function of the remainder for lags `0..=max_lag`.
+    pub fn remainder_acf(&self, max_lag: usize) -> Vec<f64> {
+        diagnostics::acf(self.remainder(), max_lag)
+    }
+
+    /// Returns the Ljung-Box test of the remainder.
+    #[cfg(feature = "std")]
+    pub fn ljung_box(&self, lags: usize) -> PortmanteauTest {
+        diagnostics::ljung_box(self.remainder(), lags)
+    }
+
+    /// Returns the Box-Pierce test of the remainder.
+    #[cfg(feature = "std")]
+    pub fn box_pierce(&self, lags: usize) -> PortmanteauTest {
+        diagnostics::box_pierce(self.remainder(), lags)
+    }
+
+    /// Returns summary statistics of the remainder.
+    pub fn remainder_stats(&self) -> RemainderStats {
+        diagnostics::remainder_stats(self.remainder())
+    }
+
+    /// Returns the seasonal component arranged by cycle and phase.
+    pub fn seasonal_pattern(&self) -> SeasonalPattern<T> {
+        SeasonalPattern::new(self.seasonal(), self.period)
+    }
+
     /// Consumes the result, returning the seasonal component, trend component, remainder, and weights.
     pub fn into_parts(self) -> (Vec<T>, Vec<T>, Vec<T>, Vec<T>) {
         (self.seasonal, self.trend, self.remainder, self.weights)
@@ -0,0 +1,300 @@
+use alloc::vec::Vec;
+
+use super::regression::check_regressors;
+use super::stl_params::ensure_finite;
+use super::str_impl::{ceil, str, to_f64, Lambdas};
+use super::{Error, Float, StrResult};
+
+/// Seasonal-trend decomposition using regression (STR).
+pub struct Str;
+
+/// A set of STR parameters.
+#[derive(Clone, Debug)]
+pub struct StrParams {
+    lambdas: Lambdas,
+    regressors: Option<Vec<Vec<f64>>>,
+    max_iterations: Option<usize>,
+}
+
+impl Str {
+    /// Decomposes a time series.
+    pub fn fit<T: Float>(series: &[T], periods: &[f64]) -> Result<StrResult<T>, Error> {
+        StrParams::new().fit(series, periods)
+    }
+
+    /// Creates a new set of parameters.
+    pub fn params() -> StrParams {
+        StrParams::new()
+    }
+}
+
+impl StrParams {
+    /// Creates a new set of parameters.
+    pub fn new() -> Self {
+        Self {
+            lambdas: Lambdas {
+                trend: 100.0,
+                seasonal_time: 100.0,
+                seasonal_phase: 0.0,
+                seasonal_cross: 10.0,
+            },
+            regressors: None,
+            max_iterations: None,
+        }
+    }
+
+    /// Sets the smoothness penalty for the trend.
+    pub fn trend_lambda(&mut self, lambda: f64) -> &mut Self {
+        self.lambdas.trend = lambda;
+        self
+    }
+
+    /// Sets the penalty for changes in the seasonal components from cycle to cycle.
+    pub fn seasonal_time_lambda(&mut self, lambda: f64) -> &mut Self {
+        self.lambdas.seasonal_time = lambda;
+        self
+    }
+
+    /// Sets the smoothness penalty for the seasonal components within a cycle.
+    pub fn seasonal_phase_lambda(&mut self, lambda: f64) -> &mut Self {
+        self.lambdas.seasonal_phase = lambda;
+        self
+    }
+
+    /// Sets the penalty for changes in the shape of the seasonal components from cycle to cycle.
+    pub fn seasonal_cross_lambda(&mut self, lambda: f64) -> &mut Self {
+        self.lambdas.seasonal_cross = lambda;
+        self
+    }
+
+    /// Sets regressors for covariates, each with the same length as the series.
+    pub fn regressors(&mut self, regressors: &[Vec<f64>]) -> &mut Self {
+        self.regressors = Some(regressors.to_vec());
+        self
+    }
+
+    /// Sets the maximum number of solver iterations.
+    pub fn max_iterations(&mut self, max_iterations: usize) -> &mut Self {
+        self.max_iterations = Some(max_iterations);
+        self
+    }
+
+    /// Decomposes a time series.
+    ///
+    /// Periods can be fractional, like 365.25 for yearly seasonality in daily data.
+    pub fn fit<T: Float>(&self, series: &[T], periods: &[f64]) -> Result<StrResult<T>, Error> {
+        for &np in periods {
+            if !(np > 1.0 && np.is_finite()) {
+                return Err(Error::float_parameter(
+                    "period",
+                    np,
+                    "must be greater than 1",
+                ));
+            }
+        }
+
+        let n = series.len();
+        let max_period = periods.iter().copied().fold(1.0, f64::max);
+        let min_len = ceil(2.0 * max_period).max(3);
+        if n < min_len {
+            return Err(Error::SeriesTooShort { len: n, min_len });
+        }
+
+        ensure_finite(series)?;
+
+        for (name, lambda) in [
+            ("trend_lambda", self.lambdas.trend),
+            ("seasonal_time_lambda", self.lambdas.seasonal_time),
+            ("seasonal_phase_lambda", self.lambdas.seasonal_phase),
+            ("seasonal_cross_lambda", self.lambdas.seasonal_cross),
+        ] {
+            if !(lambda >= 0.0 && lambda.is_finite()) {
+                return Err(Error::float_parameter(name, lambda, "must be non-negative"));
+            }
+        }
+
+        if self.max_iterations == Some(0) {
+            return Err(Error::parameter("max_iterations", 0, "must be at least 1"));
+        }
+
+        let empty = Vec::new();
+        let regressors = self.regressors.as_ref().unwrap_or(&empty);
+        check_regressors(regressors, n)?;
+
+        let y = to_f64(series);
+        let output = str(&y, periods, regressors, self.lambdas, self.max_iterations)?;
+
+        let seasonal = output
+            .seasonal
+            .iter()
+            .map(|s| s.iter().map(|&v| T::from_f64(v)).collect::<Vec<T>>())
+            .collect::<Vec<_>>();
+        let regression = (0..n)
+            .map(|i| {
+                regressors
+                    .iter()
+                    .zip(&output.coefficients)
+                    .map(|(z, b)| z[i] * b)
+                    .sum::<f64>()
+            })
+            .collect::<Vec<f64>>();
+        let remainder = (0..n)
+            .map(|i| {
+                let s = output.seasonal.iter().map(|s| s[i]).sum::<f64>();
+                T::from_f64(y[i] - s - output.trend[i] - regression[i])
+            })
+            .collect();
+
+        let has_regressors = self.regressors.is_some();
+        Ok(StrResult {
+            seasonal,
+            trend: output.trend.iter().map(|&v| T::from_f64(v)).collect(),
+            remainder,
+            periods: periods.to_vec(),
+            regression: has_regressors
+                .then(|| regression.iter().map(|&v| T::from_f64(v)).collect()),
+            coefficients: has_regressors.then_some(output.coefficients),
+        })
+    }
+}
+
+impl Default for StrParams {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stl::test_helpers::*;
+    use crate::{Error, ParameterValue, Str};
+    use alloc::{vec, vec::Vec};
+
+    // a seasonal pattern with a trend and noise
+    fn generate_series() -> Vec<f64> {
+        let pattern = [1.0, 3.0, -2.0, 0.5, 2.0, -1.5, -3.0];
+        let noise = [0.3, -0.2, 0.1, -0.4, 0.2, 0.0, -0.1, 0.4, -0.3, 0.1, 0.2];
+        (0..84)
+            .map(|i| 10.0 + 0.05 * i as f64 + pattern[i % 7] + noise[i % 11])
+            .collect()
+    }
+
+    #[test]
+    fn test_fit() {
+        let series = generate_series();
+        let result = Str::fit(&series, &[7.0]).unwrap();
+        assert_eq!(1, result.seasonal().len());
+        let pattern = [1.0, 3.0, -2.0, 0.5, 2.0, -1.5, -3.0];
+        for (i, &v) in result.seasonal()[0].iter().enumerate().skip(7).take(70) {
+            assert!((v - pattern[i % 7]).abs() < 0.4, "{} {}", i, v);
+        }
+        for (i, &v) in result.trend().iter().enumerate().skip(7).take(70) {
+            assert!((v - 10.0 - 0.05 * i as f64).abs() < 0.3, "{} {}", i, v);
+        }
+        for (i, &v) in series.iter().enumerate() {
+            assert_in_delta(
+                v,
+                result.seasonal()[0][i] + result.trend()[i] + result.remainder()[i],
+            );
+        }
+        assert!(result.seasonal_strength()[0] > 0.9);
+        assert!(result.regression().is_none());
+    }
+
+    #[test]
+    fn test_fractional() {
+        // a cycle of 3.5 points
+        let series = (0..70)
+            .map(|i| {
+                let phase = (2 * i % 7) as f32;
+                5.0 + 0.1 * i as f32 + if phase < 3.5 { 1.0 } else { -1.0 }
+            })
+            .collect::<Vec<f32>>();
+        let result = Str::fit(&series, &[3.5]).unwrap();
+        assert!(result.seasonal_strength()[0] > 0.8);
+        assert_eq!(&[3.5], result.periods());
+    }
+
+    #[test]
+    fn test_multiple() {
+        let series = (0..120)
+            .map(|i| {
+                let daily = [0.0, 2.0, -1.0, 1.0][i % 4];
+                let weekly = [3.0, 1.0, -1.0, -2.0, -4.0, 0.0, 1.0, 1.0, 0.0, 1.0][i % 10];
+                20.0 + daily + weekly
+            })
+            .collect::<Vec<f64>>();
+        let result = Str::fit(&series, &[4.0, 10.0]).unwrap();
+        assert_eq!(2, result.seasonal().len());
+        assert_eq!(2, result.seasonal_strength().len());
+        assert!(result.remainder().iter().all(|v| v.abs() < 0.5));
+    }
+
+    #[test]
+    fn test_regressors() {
+        let mut series = generate_series();
+        let mut holidays = vec![0.0; series.len()];
+        for i in [10, 45, 60] {
+            holidays[i] = 1.0;
+            series[i] += 8.0;
+        }
+        let result = Str::params()
+            .regressors(&[holidays])
+            .fit(&series, &[7.0])
+            .unwrap();
+        let coefficients = result.coefficients().unwrap();
+        assert!((coefficients[0] - 8.0).abs() < 0.5, "{}", coefficients[0]);
+        let regression = result.regression().unwrap();
+        assert_in_delta(coefficients[0], regression[10]);
+    }
+
+    #[test]
+    fn test_trend_only() {
+        let series = generate_series();
+        let result = Str::params().trend_lambda(1.0).fit(&series, &[]).unwrap();
+        assert!(result.seasonal().is_empty());
+        for (i, &v) in series.iter().enumerate() {
+            assert_in_delta(v, result.trend()[i] + result.remainder()[i]);
+        }
+    }
+
+    #[test]
+    fn test_bad_period() {
+        let result = Str::fit(&generate_series(), &[1.0]);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::InvalidParameter {
+                name: "period",
+                value: ParameterValue::Float(1.0),
+                reason: "must be greater than 1",
+            }
+        );
+    }
+
+    #[test]
+    fn test_too_short() {
+        let result = Str::fit(&generate_series(), &[42.5]);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::SeriesTooShort {
+                len: 84,
+                min_len: 85
+            }
+        );
+    }
+
+    #[test]
+    fn test_bad_lambda() {
+        let result = Str::params()
+            .trend_lambda(-1.0)
+            .fit(&generate_series(), &[7.0]);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::InvalidParameter {
+                name: "trend_lambda",
+                value: ParameterValue::Float(-1.0),
+                reason: "must be non-negative",
+            }
+        );
+    }
+}
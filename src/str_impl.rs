@@ -0,0 +1,493 @@
+// Dokumentov, A., & Hyndman, R. J. (2022).
+// STR: Seasonal-Trend Decomposition Using Regression.
+// INFORMS Journal on Data Science, 1(1), 50-62.
+//
+// The trend has a value at each time point, and each seasonal component is a surface
+// with knots at each cycle (in time) and at each phase (around the season). The value
+// of a seasonal component at a time point is bilinear interpolation on its surface,
+// which allows fractional periods. Surfaces are centered over the phase, so seasonal
+// components sum to about zero over each cycle. Components are estimated jointly by
+// penalized least squares, solved with conjugate gradients.
+
+use alloc::{vec, vec::Vec};
+
+use super::{Error, Float};
+
+// the smoothness penalties
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Lambdas {
+    pub(crate) trend: f64,
+    pub(crate) seasonal_time: f64,
+    pub(crate) seasonal_phase: f64,
+    pub(crate) seasonal_cross: f64,
+}
+
+pub(crate) struct StrOutput {
+    pub(crate) seasonal: Vec<Vec<f64>>,
+    pub(crate) trend: Vec<f64>,
+    pub(crate) coefficients: Vec<f64>,
+}
+
+struct Surface {
+    period: f64,
+    // knots in time
+    cycles: usize,
+    // knots in phase
+    phases: usize,
+    offset: usize,
+}
+
+struct Problem<'a> {
+    n: usize,
+    surfaces: Vec<Surface>,
+    regressors: &'a [Vec<f64>],
+    lambdas: Lambdas,
+    dim: usize,
+}
+
+pub(crate) fn str(
+    y: &[f64],
+    periods: &[f64],
+    regressors: &[Vec<f64>],
+    lambdas: Lambdas,
+    max_iterations: Option<usize>,
+) -> Result<StrOutput, Error> {
+    let n = y.len();
+    let mut dim = n;
+    let mut surfaces = Vec::with_capacity(periods.len());
+    for &period in periods {
+        let cycles = ((n - 1) as f64 / period) as usize + 2;
+        let phases = ceil(period).max(3);
+        surfaces.push(Surface {
+            period,
+            cycles,
+            phases,
+            offset: dim,
+        });
+        dim += cycles * phases;
+    }
+    let problem = Problem {
+        n,
+        surfaces,
+        regressors,
+        lambdas,
+        dim: dim + regressors.len(),
+    };
+
+    let b = problem.adjoint(y);
+    // conjugate gradients converge in at most dim iterations in exact arithmetic,
+    // but can take longer with rounding
+    let max_iterations = max_iterations.unwrap_or(20 * problem.dim);
+    let x = problem.conjugate_gradient(&b, max_iterations)?;
+
+    let seasonal = problem
+        .surfaces
+        .iter()
+        .map(|s| {
+            let h = s.centered(&x);
+            (0..n).map(|t| s.value(&h, t)).collect()
+        })
+        .collect();
+    Ok(StrOutput {
+        seasonal,
+        trend: x[..n].to_vec(),
+        coefficients: x[dim..].to_vec(),
+    })
+}
+
+pub(crate) fn ceil(x: f64) -> usize {
+    let v = x as usize;
+    if (v as f64) < x {
+        v + 1
+    } else {
+        v
+    }
+}
+
+impl Surface {
+    fn len(&self) -> usize {
+        self.cycles * self.phases
+    }
+
+    // the knots and weights for a time point
+    fn corners(&self, t: usize) -> [(usize, f64); 4] {
+        let u = t as f64 / self.period;
+        let i = u as usize;
+        let fu = u - i as f64;
+        let v = fu * self.phases as f64;
+        let j = (v as usize).min(self.phases - 1);
+        let fv = v - j as f64;
+        let j1 = (j + 1) % self.phases;
+        let m = self.phases;
+        [
+            (i * m + j, (1.0 - fu) * (1.0 - fv)),
+            (i * m + j1, (1.0 - fu) * fv),
+            ((i + 1) * m + j, fu * (1.0 - fv)),
+            ((i + 1) * m + j1, fu * fv),
+        ]
+    }
+
+    fn value(&self, h: &[f64], t: usize) -> f64 {
+        self.corners(t).iter().map(|&(k, w)| w * h[k]).sum()
+    }
+
+    // subtracts the mean over the phase for each cycle, which is its own adjoint
+    fn center(&self, g: &mut [f64]) {
+        for row in g.chunks_exact_mut(self.phases) {
+            let mean = row.iter().sum::<f64>() / self.phases as f64;
+            for v in row {
+                *v -= mean;
+            }
+        }
+    }
+
+    fn centered(&self, x: &[f64]) -> Vec<f64> {
+        let mut h = x[self.offset..self.offset + self.len()].to_vec();
+        self.center(&mut h);
+        h
+    }
+
+    // adds the penalty gradient for the centered surface h to gh
+    fn penalty(&self, h: &[f64], gh: &mut [f64], lambdas: &Lambdas) {
+        let m = self.phases;
+        let c = self.cycles;
+
+        // second differences in time at each phase
+        if lambdas.seasonal_time > 0.0 {
+            for i in 1..c - 1 {
+                for j in 0..m {
+                    let d = h[(i - 1) * m + j] - 2.0 * h[i * m + j] + h[(i + 1) * m + j];
+                    let d = lambdas.seasonal_time * d;
+                    gh[(i - 1) * m + j] += d;
+                    gh[i * m + j] -= 2.0 * d;
+                    gh[(i + 1) * m + j] += d;
+                }
+            }
+        }
+
+        // circular second differences in phase for each cycle
+        if lambdas.seasonal_phase > 0.0 {
+            for i in 0..c {
+                for j in 0..m {
+                    let a = i * m + (j + m - 1) % m;
+                    let b = i * m + j;
+                    let e = i * m + (j + 1) % m;
+                    let d = lambdas.seasonal_phase * (h[a] - 2.0 * h[b] + h[e]);
+                    gh[a] += d;
+                    gh[b] -= 2.0 * d;
+                    gh[e] += d;
+                }
+            }
+        }
+
+        // mixed differences in time and phase
+        if lambdas.seasonal_cross > 0.0 {
+            for i in 0..c - 1 {
+                for j in 0..m {
+                    let j1 = (j + 1) % m;
+                    let k = [i * m + j, i * m + j1, (i + 1) * m + j, (i + 1) * m + j1];
+                    let d = lambdas.seasonal_cross * (h[k[3]] - h[k[2]] - h[k[1]] + h[k[0]]);
+                    gh[k[0]] += d;
+                    gh[k[1]] -= d;
+                    gh[k[2]] -= d;
+                    gh[k[3]] += d;
+                }
+            }
+        }
+    }
+}
+
+impl Problem<'_> {
+    // the fitted values for the parameters
+    fn forward(&self, x: &[f64], centered: &[Vec<f64>]) -> Vec<f64> {
+        let mut fit = x[..self.n].to_vec();
+        for (s, h) in self.surfaces.iter().zip(centered) {
+            for (t, f) in fit.iter_mut().enumerate() {
+                *f += s.value(h, t);
+            }
+        }
+        let start = self.dim - self.regressors.len();
+        for (z, &b) in self.regressors.iter().zip(&x[start..]) {
+            for (f, v) in fit.iter_mut().zip(z) {
+                *f += v * b;
+            }
+        }
+        fit
+    }
+
+    // the adjoint of forward
+    fn adjoint(&self, r: &[f64]) -> Vec<f64> {
+        let mut g = vec![0.0; self.dim];
+        g[..self.n].copy_from_slice(r);
+        for s in &self.surfaces {
+            let gs = &mut g[s.offset..s.offset + s.len()];
+            for (t, &v) in r.iter().enumerate() {
+                for (k, w) in s.corners(t) {
+                    gs[k] += w * v;
+                }
+            }
+            s.center(gs);
+        }
+        let start = self.dim - self.regressors.len();
+        for (z, gb) in self.regressors.iter().zip(&mut g[start..]) {
+            *gb = z.iter().zip(r).map(|(a, b)| a * b).sum();
+        }
+        g
+    }
+
+    // the normal equations with penalties
+    fn apply(&self, x: &[f64]) -> Vec<f64> {
+        let centered = self
+            .surfaces
+            .iter()
+            .map(|s| s.centered(x))
+            .collect::<Vec<_>>();
+        let mut g = self.adjoint(&self.forward(x, &centered));
+
+        // second differences of the trend
+        let lambda = self.lambdas.trend;
+        if lambda > 0.0 {
+            for t in 1..self.n - 1 {
+                let d = lambda * (x[t - 1] - 2.0 * x[t] + x[t + 1]);
+                g[t - 1] += d;
+                g[t] -= 2.0 * d;
+                g[t + 1] += d;
+            }
+        }
+
+        for (s, h) in self.surfaces.iter().zip(&centered) {
+            let mut gh = vec![0.0; s.len()];
+            s.penalty(h, &mut gh, &self.lambdas);
+            s.center(&mut gh);
+            for (a, b) in g[s.offset..s.offset + s.len()].iter_mut().zip(&gh) {
+                *a += b;
+            }
+        }
+        g
+    }
+
+    // preconditioned conjugate gradients
+    fn conjugate_gradient(&self, b: &[f64], max_iterations: usize) -> Result<Vec<f64>, Error> {
+        let preconditioner = Preconditioner::new(self);
+        let precondition = |r: &[f64], z: &mut [f64]| preconditioner.solve(self, r, z);
+        let mut x = vec![0.0; self.dim];
+        let mut r = b.to_vec();
+        let mut z = vec![0.0; self.dim];
+        precondition(&r, &mut z);
+        let mut p = z.clone();
+        let mut rz = dot(&r, &z);
+        let bb = dot(b, b);
+        let tol = 1e-12 * bb;
+        for _ in 0..max_iterations {
+            if dot(&r, &r) <= tol {
+                return Ok(x);
+            }
+            let ap = self.apply(&p);
+            let pap = dot(&p, &ap);
+            if pap <= 0.0 {
+                break;
+            }
+            let alpha = rz / pap;
+            for ((xi, ri), (pi, api)) in x.iter_mut().zip(&mut r).zip(p.iter().zip(&ap)) {
+                *xi += alpha * pi;
+                *ri -= alpha * api;
+            }
+            precondition(&r, &mut z);
+            let next = dot(&r, &z);
+            let beta = next / rz;
+            rz = next;
+            for (pi, zi) in p.iter_mut().zip(&z) {
+                *pi = zi + beta * *pi;
+            }
+        }
+        if dot(&r, &r) <= tol {
+            Ok(x)
+        } else {
+            Err(Error::NotConverged {
+                iterations: max_iterations,
+            })
+        }
+    }
+}
+
+// solves the blocks of the normal equations for the trend and for each phase
+// of each seasonal surface, ignoring the coupling between blocks
+struct Preconditioner {
+    trend: BandFactor,
+    // for each surface, a factor for each phase over the cycles
+    surfaces: Vec<Vec<BandFactor>>,
+    regressors: Vec<f64>,
+}
+
+impl Preconditioner {
+    fn new(problem: &Problem) -> Self {
+        let n = problem.n;
+        let lambdas = &problem.lambdas;
+        let trend = BandFactor::new(&vec![1.0; n], lambdas.trend);
+        let surfaces = problem
+            .surfaces
+            .iter()
+            .map(|s| {
+                let mut d = vec![0.0; s.len()];
+                for t in 0..n {
+                    for (k, w) in s.corners(t) {
+                        d[k] += w * w;
+                    }
+                }
+                (0..s.phases)
+                    .map(|j| {
+                        let base = (0..s.cycles)
+                            .map(|i| {
+                                let cross = if i == 0 || i + 1 == s.cycles {
+                                    2.0
+                                } else {
+                                    4.0
+                                };
+                                d[i * s.phases + j]
+                                    + lambdas.seasonal_phase * 6.0
+                                    + lambdas.seasonal_cross * cross
+                            })
+                            .collect::<Vec<f64>>();
+                        BandFactor::new(&base, lambdas.seasonal_time)
+                    })
+                    .collect()
+            })
+            .collect();
+        let regressors = problem
+            .regressors
+            .iter()
+            .map(|z| dot(z, z).max(f64::MIN_POSITIVE))
+            .collect();
+        Self {
+            trend,
+            surfaces,
+            regressors,
+        }
+    }
+
+    fn solve(&self, problem: &Problem, r: &[f64], z: &mut [f64]) {
+        let n = problem.n;
+        self.trend.solve(&r[..n], &mut z[..n]);
+        let mut rj = Vec::new();
+        let mut zj = Vec::new();
+        for (s, factors) in problem.surfaces.iter().zip(&self.surfaces) {
+            let rs = &r[s.offset..s.offset + s.len()];
+            let zs = &mut z[s.offset..s.offset + s.len()];
+            for (j, factor) in factors.iter().enumerate() {
+                rj.clear();
+                rj.extend(rs.iter().skip(j).step_by(s.phases));
+                zj.resize(rj.len(), 0.0);
+                factor.solve(&rj, &mut zj);
+                for (v, &w) in zs.iter_mut().skip(j).step_by(s.phases).zip(&zj) {
+                    *v = w;
+                }
+            }
+        }
+        let start = problem.dim - problem.regressors.len();
+        for ((zi, ri), d) in z[start..].iter_mut().zip(&r[start..]).zip(&self.regressors) {
+            *zi = ri / d;
+        }
+    }
+}
+
+// the Cholesky factor of diag(base) + lambda D'D, where D takes second differences
+struct BandFactor {
+    // the diagonal and the two subdiagonals of L
+    l0: Vec<f64>,
+    l1: Vec<f64>,
+    l2: Vec<f64>,
+}
+
+impl BandFactor {
+    fn new(base: &[f64], lambda: f64) -> Self {
+        let n = base.len();
+        let penalized = n >= 3;
+        let mut l0 = vec![0.0; n];
+        let mut l1 = vec![0.0; n];
+        let mut l2 = vec![0.0; n];
+        for i in 0..n {
+            // entries (i, i - 2) and (i, i - 1) of the matrix
+            let a2 = if penalized { lambda } else { 0.0 };
+            let a1 = if !penalized {
+                0.0
+            } else if i == 1 || i + 1 == n {
+                -2.0 * lambda
+            } else {
+                -4.0 * lambda
+            };
+            if i >= 2 {
+                l2[i] = a2 / l0[i - 2];
+            }
+            if i >= 1 {
+                let mut v = a1;
+                if i >= 2 {
+                    v -= l2[i] * l1[i - 1];
+                }
+                l1[i] = v / l0[i - 1];
+            }
+            let mut v = base[i] + lambda * second_difference_count(i, n);
+            if i >= 1 {
+                v -= l1[i] * l1[i];
+            }
+            if i >= 2 {
+                v -= l2[i] * l2[i];
+            }
+            // points without observations or penalties are in the null space
+            l0[i] = if v > 0.0 { Float::sqrt(&v) } else { 1.0 };
+        }
+        Self { l0, l1, l2 }
+    }
+
+    fn solve(&self, b: &[f64], x: &mut [f64]) {
+        let n = b.len();
+        // forward substitution
+        for i in 0..n {
+            let mut v = b[i];
+            if i >= 1 {
+                v -= self.l1[i] * x[i - 1];
+            }
+            if i >= 2 {
+                v -= self.l2[i] * x[i - 2];
+            }
+            x[i] = v / self.l0[i];
+        }
+        // back substitution
+        for i in (0..n).rev() {
+            let mut v = x[i];
+            if i + 1 < n {
+                v -= self.l1[i + 1] * x[i + 1];
+            }
+            if i + 2 < n {
+                v -= self.l2[i + 2] * x[i + 2];
+            }
+            x[i] = v / self.l0[i];
+        }
+    }
+}
+
+// the sum of squared coefficients of second differences that include a point
+fn second_difference_count(i: usize, n: usize) -> f64 {
+    if n < 3 {
+        return 0.0;
+    }
+    let mut count = 0.0;
+    if i >= 2 {
+        count += 1.0;
+    }
+    if i >= 1 && i + 1 < n {
+        count += 4.0;
+    }
+    if i + 2 < n {
+        count += 1.0;
+    }
+    count
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+// converts a series for the solver
+pub(crate) fn to_f64<T: Float>(series: &[T]) -> Vec<f64> {
+    series.iter().map(|v| v.as_f64()).collect()
+}
@@ -0,0 +1,90 @@
+use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
+use super::diagnostics::PortmanteauTest;
+use super::diagnostics::{self, RemainderStats};
+use super::stl_result::strength;
+use super::Float;
+
+/// A STR result.
+#[derive(Clone, Debug)]
+pub struct StrResult<T: Float = f32> {
+    pub(crate) seasonal: Vec<Vec<T>>,
+    pub(crate) trend: Vec<T>,
+    pub(crate) remainder: Vec<T>,
+    pub(crate) periods: Vec<f64>,
+    pub(crate) regression: Option<Vec<T>>,
+    pub(crate) coefficients: Option<Vec<f64>>,
+}
+
+impl<T: Float> StrResult<T> {
+    /// Returns the seasonal components.
+    pub fn seasonal(&self) -> &[Vec<T>] {
+        &self.seasonal[..]
+    }
+
+    /// Returns the trend component.
+    pub fn trend(&self) -> &[T] {
+        &self.trend
+    }
+
+    /// Returns the remainder.
+    pub fn remainder(&self) -> &[T] {
+        &self.remainder
+    }
+
+    /// Returns the periods.
+    pub fn periods(&self) -> &[f64] {
+        &self.periods
+    }
+
+    /// Returns the regression component, if regressors were given.
+    pub fn regression(&self) -> Option<&[T]> {
+        self.regression.as_deref()
+    }
+
+    /// Returns the regression coefficients, if regressors were given.
+    pub fn coefficients(&self) -> Option<&[f64]> {
+        self.coefficients.as_deref()
+    }
+
+    /// Returns the seasonal strength.
+    pub fn seasonal_strength(&self) -> Vec<f64> {
+        self.seasonal()
+            .iter()
+            .map(|s| strength(s, self.remainder()))
+            .collect()
+    }
+
+    /// Returns the trend strength.
+    pub fn trend_strength(&self) -> f64 {
+        strength(self.trend(), self.remainder())
+    }
+
+    /// Returns the autocorrelation function of the remainder for lags `0..=max_lag`.
+    pub fn remainder_acf(&self, max_lag: usize) -> Vec<f64> {
+        diagnostics::acf(self.remainder(), max_lag)
+    }
+
+    /// Returns the Ljung-Box test of the remainder.
+    #[cfg(feature = "std")]
+    pub fn ljung_box(&self, lags: usize) -> PortmanteauTest {
+        diagnostics::ljung_box(self.remainder(), lags)
+    }
+
+    /// Returns the Box-Pierce test of the remainder.
+    #[cfg(feature = "std")]
+    pub fn box_pierce(&self, lags: usize) -> PortmanteauTest {
+        diagnostics::box_pierce(self.remainder(), lags)
+    }
+
+    /// Returns summary statistics of the remainder.
+    pub fn remainder_stats(&self) -> RemainderStats {
+        diagnostics::remainder_stats(self.remainder())
+    }
+
+    /// Consumes the result, returning the seasonal components, trend component, and remainder.
+    pub fn into_parts(self) -> (Vec<Vec<T>>, Vec<T>, Vec<T>) {
+        (self.seasonal, self.trend, self.remainder)
+    }
+}
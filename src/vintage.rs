@@ -0,0 +1,391 @@
+use alloc::{vec, vec::Vec};
+use core::ops::Range;
+
+use super::mstl_impl::mstl;
+use super::{Error, Float, MstlParams, StlParams};
+
+/// Real-time decompositions, where each point is estimated using only data available at that time.
+#[derive(Clone, Debug)]
+pub struct Vintages<T: Float = f32> {
+    cutoffs: Vec<usize>,
+    seasonal: Vec<Vec<T>>,
+    trend: Vec<T>,
+    remainder: Vec<T>,
+    final_seasonal: Vec<Vec<T>>,
+    final_trend: Vec<T>,
+}
+
+/// Summary statistics of revisions.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RevisionStats {
+    /// The mean revision.
+    pub mean: f64,
+    /// The mean absolute revision.
+    pub mean_absolute: f64,
+    /// The root mean square revision.
+    pub root_mean_square: f64,
+    /// The maximum absolute revision.
+    pub max_absolute: f64,
+}
+
+impl StlParams {
+    /// Decomposes each prefix of a time series that ends at a cutoff.
+    ///
+    /// Cutoffs are prefix lengths, so the real-time estimate for the cutoff `c` is at index `c - 1`.
+    pub fn fit_vintages<T: Float>(
+        &self,
+        series: &[T],
+        period: usize,
+        cutoffs: Range<usize>,
+    ) -> Result<Vintages<T>, Error> {
+        check_cutoffs(series.len(), &cutoffs, &[period])?;
+        let full = self.fit(series, period)?;
+
+        let n = series.len();
+        let mut seasonal = vec![T::zero(); n];
+        let mut trend = vec![T::zero(); n];
+        let mut weights = vec![T::zero(); n];
+        let mut work = vec![T::zero(); (n + 2 * period) * 5];
+
+        let mut vintages = Vintages::new(cutoffs.len(), 1);
+        for end in cutoffs {
+            if self.extension > 0 {
+                let result = self.fit(&series[..end], period)?;
+                vintages.push(
+                    end,
+                    &[result.seasonal()[end - 1]],
+                    result.trend()[end - 1],
+                    series[end - 1],
+                );
+            } else {
+                self.fit_impl(
+                    &series[..end],
+                    period,
+                    &mut seasonal[..end],
+                    &mut trend[..end],
+                    &mut weights[..end],
+                    &mut work,
+                )?;
+                vintages.push(end, &[seasonal[end - 1]], trend[end - 1], series[end - 1]);
+            }
+            vintages.push_final(&[full.seasonal()], full.trend(), end);
+        }
+        Ok(vintages)
+    }
+}
+
+impl MstlParams {
+    /// Decomposes each prefix of a time series that ends at a cutoff.
+    ///
+    /// Cutoffs are prefix lengths, so the real-time estimate for the cutoff `c` is at index `c - 1`.
+    pub fn fit_vintages<T: Float>(
+        &self,
+        series: &[T],
+        periods: &[usize],
+        cutoffs: Range<usize>,
+    ) -> Result<Vintages<T>, Error> {
+        check_cutoffs(series.len(), &cutoffs, periods)?;
+        let full = self.fit(series, periods)?;
+        let full_seasonal = full.seasonal().iter().map(|s| &s[..]).collect::<Vec<_>>();
+
+        let n = series.len();
+        let mut seasonal = vec![Vec::with_capacity(n); periods.len()];
+        let mut trend = vec![T::zero(); n];
+        let mut remainder = vec![T::zero(); n];
+        let mut weights = vec![T::zero(); n];
+        let mut work = vec![T::zero(); self.work_len(n, periods)];
+        let mut projected = vec![Vec::new(); periods.len()];
+
+        let mut vintages = Vintages::new(cutoffs.len(), periods.len());
+        for end in cutoffs {
+            if self.extension > 0 || self.regressors.is_some() {
+                let mut params = self.clone();
+                if let Some(regressors) = &self.regressors {
+                    let prefixes = regressors
+                        .iter()
+                        .map(|r| r[..end].to_vec())
+                        .collect::<Vec<_>>();
+                    params.regressors(&prefixes);
+                }
+                let result = params.fit(&series[..end], periods)?;
+                let last = result
+                    .seasonal()
+                    .iter()
+                    .map(|s| s[end - 1])
+                    .collect::<Vec<T>>();
+                let observed =
+                    series[end - 1] - result.regression().map(|r| r[end - 1]).unwrap_or(T::zero());
+                vintages.push(end, &last, result.trend()[end - 1], observed);
+            } else {
+                // resizing within capacity keeps the allocations
+                for s in &mut seasonal {
+                    s.resize(end, T::zero());
+                }
+                mstl(
+                    &series[..end],
+                    periods,
+                    self.iterate,
+                    self.lambda,
+                    &self.swin,
+                    &self.fourier,
+                    &self.stl_params,
+                    &mut seasonal,
+                    &mut trend[..end],
+                    &mut remainder[..end],
+                    &mut weights[..end],
+                    &mut work,
+                    &mut projected,
+                )?;
+                let last = seasonal.iter().map(|s| s[end - 1]).collect::<Vec<T>>();
+                vintages.push_remainder(end, &last, trend[end - 1], remainder[end - 1]);
+            }
+            vintages.push_final(&full_seasonal, full.trend(), end);
+        }
+        Ok(vintages)
+    }
+}
+
+fn check_cutoffs(n: usize, cutoffs: &Range<usize>, periods: &[usize]) -> Result<(), Error> {
+    if cutoffs.is_empty() {
+        return Err(Error::parameter("cutoffs", 0, "must not be empty"));
+    }
+    if cutoffs.end > n + 1 {
+        return Err(Error::parameter(
+            "cutoffs",
+            cutoffs.end - 1,
+            "must not exceed the series length",
+        ));
+    }
+    let min_len = 2 * periods.iter().copied().max().unwrap_or(0);
+    if cutoffs.start < min_len {
+        return Err(Error::SeriesTooShort {
+            len: cutoffs.start,
+            min_len,
+        });
+    }
+    Ok(())
+}
+
+impl<T: Float> Vintages<T> {
+    fn new(len: usize, components: usize) -> Self {
+        Self {
+            cutoffs: Vec::with_capacity(len),
+            seasonal: vec![Vec::with_capacity(len); components],
+            trend: Vec::with_capacity(len),
+            remainder: Vec::with_capacity(len),
+            final_seasonal: vec![Vec::with_capacity(len); components],
+            final_trend: Vec::with_capacity(len),
+        }
+    }
+
+    fn push(&mut self, end: usize, seasonal: &[T], trend: T, observed: T) {
+        let remainder = observed - seasonal.iter().copied().sum::<T>() - trend;
+        self.push_remainder(end, seasonal, trend, remainder);
+    }
+
+    fn push_remainder(&mut self, end: usize, seasonal: &[T], trend: T, remainder: T) {
+        self.cutoffs.push(end);
+        for (s, &v) in self.seasonal.iter_mut().zip(seasonal) {
+            s.push(v);
+        }
+        self.trend.push(trend);
+        self.remainder.push(remainder);
+    }
+
+    fn push_final(&mut self, seasonal: &[&[T]], trend: &[T], end: usize) {
+        for (s, v) in self.final_seasonal.iter_mut().zip(seasonal) {
+            s.push(v[end - 1]);
+        }
+        self.final_trend.push(trend[end - 1]);
+    }
+
+    /// Returns the cutoffs.
+    pub fn cutoffs(&self) -> &[usize] {
+        &self.cutoffs
+    }
+
+    /// Returns the real-time seasonal components.
+    pub fn seasonal(&self) -> &[Vec<T>] {
+        &self.seasonal
+    }
+
+    /// Returns the real-time trend component.
+    pub fn trend(&self) -> &[T] {
+        &self.trend
+    }
+
+    /// Returns the real-time remainder.
+    pub fn remainder(&self) -> &[T] {
+        &self.remainder
+    }
+
+    /// Returns the seasonal components from the full series at the same points.
+    pub fn final_seasonal(&self) -> &[Vec<T>] {
+        &self.final_seasonal
+    }
+
+    /// Returns the trend component from the full series at the same points.
+    pub fn final_trend(&self) -> &[T] {
+        &self.final_trend
+    }
+
+    /// Returns the revisions to the seasonal components (final minus real-time).
+    pub fn seasonal_revisions(&self) -> Vec<Vec<T>> {
+        self.seasonal
+            .iter()
+            .zip(&self.final_seasonal)
+            .map(|(s, f)| revisions(s, f))
+            .collect()
+    }
+
+    /// Returns the revisions to the trend component (final minus real-time).
+    pub fn trend_revisions(&self) -> Vec<T> {
+        revisions(&self.trend, &self.final_trend)
+    }
+
+    /// Returns summary statistics of the seasonal revisions.
+    pub fn seasonal_revision_stats(&self) -> Vec<RevisionStats> {
+        self.seasonal_revisions()
+            .iter()
+            .map(|r| revision_stats(r))
+            .collect()
+    }
+
+    /// Returns summary statistics of the trend revisions.
+    pub fn trend_revision_stats(&self) -> RevisionStats {
+        revision_stats(&self.trend_revisions())
+    }
+}
+
+fn revisions<T: Float>(real_time: &[T], last: &[T]) -> Vec<T> {
+    real_time.iter().zip(last).map(|(&r, &f)| f - r).collect()
+}
+
+fn revision_stats<T: Float>(revisions: &[T]) -> RevisionStats {
+    let n = revisions.len() as f64;
+    let mut sum = 0.0;
+    let mut sum_abs = 0.0;
+    let mut sum_sq = 0.0;
+    let mut max_abs = 0.0;
+    for v in revisions {
+        let v = v.as_f64();
+        sum += v;
+        sum_abs += Float::abs(&v);
+        sum_sq += v * v;
+        max_abs = Float::max(&max_abs, Float::abs(&v));
+    }
+    RevisionStats {
+        mean: sum / n,
+        mean_absolute: sum_abs / n,
+        root_mean_square: Float::sqrt(&(sum_sq / n)),
+        max_absolute: max_abs,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stl::test_helpers::*;
+    use crate::{Error, Mstl, ParameterValue, Stl};
+    use alloc::{vec, vec::Vec};
+
+    fn generate_series() -> Vec<f32> {
+        vec![
+            5.0, 9.0, 2.0, 9.0, 0.0, 6.0, 3.0, 8.0, 5.0, 8.0, 7.0, 8.0, 8.0, 0.0, 2.0, 5.0, 0.0,
+            5.0, 6.0, 7.0, 3.0, 6.0, 1.0, 4.0, 4.0, 4.0, 3.0, 7.0, 5.0, 8.0,
+        ]
+    }
+
+    #[test]
+    fn test_stl() {
+        let series = generate_series();
+        let vintages = Stl::params().fit_vintages(&series, 7, 14..31).unwrap();
+        assert_eq!(17, vintages.cutoffs().len());
+        assert_eq!(14, vintages.cutoffs()[0]);
+
+        // each point matches a fit on the prefix
+        for (i, &end) in vintages.cutoffs().iter().enumerate() {
+            let expected = Stl::fit(&series[..end], 7).unwrap();
+            assert_in_delta(expected.trend()[end - 1], vintages.trend()[i]);
+            assert_in_delta(expected.seasonal()[end - 1], vintages.seasonal()[0][i]);
+            assert_in_delta(expected.remainder()[end - 1], vintages.remainder()[i]);
+        }
+
+        // the last cutoff is the full series, so it has no revision
+        let full = Stl::fit(&series, 7).unwrap();
+        assert_in_delta(full.trend()[20], vintages.final_trend()[7]);
+        assert_in_delta(0.0, *vintages.trend_revisions().last().unwrap());
+
+        let stats = vintages.trend_revision_stats();
+        let revisions = vintages.trend_revisions();
+        let max = revisions.iter().map(|v| v.abs()).fold(0.0, f32::max);
+        assert_in_delta(max as f64, stats.max_absolute);
+        assert!(stats.mean_absolute <= stats.root_mean_square);
+    }
+
+    #[test]
+    fn test_stl_robust() {
+        let series = generate_series();
+        let vintages = Stl::params()
+            .robust(true)
+            .fit_vintages(&series, 7, 20..25)
+            .unwrap();
+        let expected = Stl::params().robust(true).fit(&series[..22], 7).unwrap();
+        assert_in_delta(expected.trend()[21], vintages.trend()[2]);
+    }
+
+    #[test]
+    fn test_mstl() {
+        let series = generate_series();
+        let vintages = Mstl::params()
+            .fit_vintages(&series, &[6, 10], 20..31)
+            .unwrap();
+        assert_eq!(2, vintages.seasonal().len());
+        for (i, &end) in vintages.cutoffs().iter().enumerate() {
+            let expected = Mstl::fit(&series[..end], &[6, 10]).unwrap();
+            assert_in_delta(expected.trend()[end - 1], vintages.trend()[i]);
+            assert_in_delta(expected.seasonal()[1][end - 1], vintages.seasonal()[1][i]);
+            assert_in_delta(expected.remainder()[end - 1], vintages.remainder()[i]);
+        }
+        assert_eq!(2, vintages.seasonal_revision_stats().len());
+    }
+
+    #[test]
+    fn test_mstl_extension() {
+        let series = generate_series();
+        let vintages = Mstl::params()
+            .extension(5)
+            .fit_vintages(&series, &[6, 10], 25..31)
+            .unwrap();
+        let expected = Mstl::params()
+            .extension(5)
+            .fit(&series[..27], &[6, 10])
+            .unwrap();
+        assert_in_delta(expected.trend()[26], vintages.trend()[2]);
+        assert_in_delta(expected.remainder()[26], vintages.remainder()[2]);
+    }
+
+    #[test]
+    fn test_cutoff_too_short() {
+        let result = Stl::params().fit_vintages(&generate_series(), 7, 10..31);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::SeriesTooShort {
+                len: 10,
+                min_len: 14
+            }
+        );
+    }
+
+    #[test]
+    fn test_cutoff_past_end() {
+        let result = Stl::params().fit_vintages(&generate_series(), 7, 14..32);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::InvalidParameter {
+                name: "cutoffs",
+                value: ParameterValue::Integer(31),
+                reason: "must not exceed the series length",
+            }
+        );
+    }
+}
@@ -0,0 +1,333 @@
+use js_sys::{Array, Float64Array, Reflect};
+use wasm_bindgen::prelude::*;
+
+use super::{Error, MstlParams, MstlResult, StlParams, StlResult};
+
+/// Seasonal-trend decomposition using Loess (STL).
+#[wasm_bindgen(js_name = Stl)]
+pub struct WasmStl;
+
+/// A STL result.
+#[wasm_bindgen(js_name = StlResult)]
+pub struct WasmStlResult(StlResult<f64>);
+
+/// Multiple seasonal-trend decomposition using Loess (MSTL).
+#[wasm_bindgen(js_name = Mstl)]
+pub struct WasmMstl;
+
+/// A MSTL result.
+#[wasm_bindgen(js_name = MstlResult)]
+pub struct WasmMstlResult(MstlResult<f64>);
+
+#[wasm_bindgen(js_class = Stl)]
+impl WasmStl {
+    /// Decomposes a time series.
+    ///
+    /// `params` is an optional object with the same keys as the `StlParams` builder
+    /// methods in camel case, like `{ seasonalLength: 7, robust: true }`.
+    pub fn fit(
+        series: &Float64Array,
+        period: usize,
+        params: JsValue,
+    ) -> Result<WasmStlResult, JsError> {
+        let params = stl_params(&params)?;
+        let result = params.fit(&series.to_vec(), period).map_err(js_error)?;
+        Ok(WasmStlResult(result))
+    }
+}
+
+#[wasm_bindgen(js_class = StlResult)]
+impl WasmStlResult {
+    /// Returns the seasonal component.
+    #[wasm_bindgen(getter)]
+    pub fn seasonal(&self) -> Float64Array {
+        Float64Array::from(self.0.seasonal())
+    }
+
+    /// Returns the trend component.
+    #[wasm_bindgen(getter)]
+    pub fn trend(&self) -> Float64Array {
+        Float64Array::from(self.0.trend())
+    }
+
+    /// Returns the remainder.
+    #[wasm_bindgen(getter)]
+    pub fn remainder(&self) -> Float64Array {
+        Float64Array::from(self.0.remainder())
+    }
+
+    /// Returns the weights.
+    #[wasm_bindgen(getter)]
+    pub fn weights(&self) -> Float64Array {
+        Float64Array::from(self.0.weights())
+    }
+
+    /// Returns the seasonal strength.
+    #[wasm_bindgen(getter, js_name = seasonalStrength)]
+    pub fn seasonal_strength(&self) -> f64 {
+        self.0.seasonal_strength()
+    }
+
+    /// Returns the trend strength.
+    #[wasm_bindgen(getter, js_name = trendStrength)]
+    pub fn trend_strength(&self) -> f64 {
+        self.0.trend_strength()
+    }
+}
+
+#[wasm_bindgen(js_class = Mstl)]
+impl WasmMstl {
+    /// Decomposes a time series.
+    ///
+    /// `params` is an optional object with the same keys as the `MstlParams` builder
+    /// methods in camel case, like `{ iterations: 2, stlParams: { robust: true } }`.
+    pub fn fit(
+        series: &Float64Array,
+        periods: Vec<usize>,
+        params: JsValue,
+    ) -> Result<WasmMstlResult, JsError> {
+        let params = mstl_params(&params)?;
+        let result = params.fit(&series.to_vec(), &periods).map_err(js_error)?;
+        Ok(WasmMstlResult(result))
+    }
+}
+
+#[wasm_bindgen(js_class = MstlResult)]
+impl WasmMstlResult {
+    /// Returns the seasonal components.
+    #[wasm_bindgen(getter)]
+    pub fn seasonal(&self) -> Array {
+        self.0
+            .seasonal()
+            .iter()
+            .map(|s| Float64Array::from(&s[..]))
+            .collect()
+    }
+
+    /// Returns the trend component.
+    #[wasm_bindgen(getter)]
+    pub fn trend(&self) -> Float64Array {
+        Float64Array::from(self.0.trend())
+    }
+
+    /// Returns the remainder.
+    #[wasm_bindgen(getter)]
+    pub fn remainder(&self) -> Float64Array {
+        Float64Array::from(self.0.remainder())
+    }
+
+    /// Returns the seasonal strength.
+    #[wasm_bindgen(getter, js_name = seasonalStrength)]
+    pub fn seasonal_strength(&self) -> Float64Array {
+        Float64Array::from(&self.0.seasonal_strength()[..])
+    }
+
+    /// Returns the trend strength.
+    #[wasm_bindgen(getter, js_name = trendStrength)]
+    pub fn trend_strength(&self) -> f64 {
+        self.0.trend_strength()
+    }
+}
+
+fn js_error(err: Error) -> JsError {
+    JsError::new(&err.to_string())
+}
+
+fn get(params: &JsValue, key: &str) -> Result<Option<JsValue>, JsError> {
+    if params.is_undefined() || params.is_null() {
+        return Ok(None);
+    }
+    if !params.is_object() {
+        return Err(JsError::new("params must be an object"));
+    }
+    let value = Reflect::get(params, &JsValue::from_str(key))
+        .map_err(|_| JsError::new("params must be an object"))?;
+    Ok(if value.is_undefined() || value.is_null() {
+        None
+    } else {
+        Some(value)
+    })
+}
+
+fn to_usize(key: &str, value: &JsValue) -> Result<usize, JsError> {
+    match value.as_f64() {
+        Some(v) if v >= 0.0 && v.fract() == 0.0 => Ok(v as usize),
+        _ => Err(JsError::new(&format!(
+            "{} must be a non-negative integer",
+            key
+        ))),
+    }
+}
+
+fn get_usize(params: &JsValue, key: &str) -> Result<Option<usize>, JsError> {
+    get(params, key)?.map(|v| to_usize(key, &v)).transpose()
+}
+
+fn get_i32(params: &JsValue, key: &str) -> Result<Option<i32>, JsError> {
+    get(params, key)?
+        .map(|v| match v.as_f64() {
+            Some(v) if v.fract() == 0.0 => Ok(v as i32),
+            _ => Err(JsError::new(&format!("{} must be an integer", key))),
+        })
+        .transpose()
+}
+
+fn get_f32(params: &JsValue, key: &str) -> Result<Option<f32>, JsError> {
+    get(params, key)?
+        .map(|v| {
+            v.as_f64()
+                .map(|v| v as f32)
+                .ok_or_else(|| JsError::new(&format!("{} must be a number", key)))
+        })
+        .transpose()
+}
+
+fn get_bool(params: &JsValue, key: &str) -> Result<Option<bool>, JsError> {
+    get(params, key)?
+        .map(|v| {
+            v.as_bool()
+                .ok_or_else(|| JsError::new(&format!("{} must be a boolean", key)))
+        })
+        .transpose()
+}
+
+fn stl_params(params: &JsValue) -> Result<StlParams, JsError> {
+    let mut stl_params = StlParams::new();
+    if let Some(v) = get_usize(params, "seasonalLength")? {
+        stl_params.seasonal_length(v);
+    }
+    if let Some(v) = get_usize(params, "trendLength")? {
+        stl_params.trend_length(v);
+    }
+    if let Some(v) = get_usize(params, "lowPassLength")? {
+        stl_params.low_pass_length(v);
+    }
+    if let Some(v) = get_i32(params, "seasonalDegree")? {
+        stl_params.seasonal_degree(v);
+    }
+    if let Some(v) = get_i32(params, "trendDegree")? {
+        stl_params.trend_degree(v);
+    }
+    if let Some(v) = get_i32(params, "lowPassDegree")? {
+        stl_params.low_pass_degree(v);
+    }
+    if let Some(v) = get_usize(params, "seasonalJump")? {
+        stl_params.seasonal_jump(v);
+    }
+    if let Some(v) = get_usize(params, "trendJump")? {
+        stl_params.trend_jump(v);
+    }
+    if let Some(v) = get_usize(params, "lowPassJump")? {
+        stl_params.low_pass_jump(v);
+    }
+    if let Some(v) = get_usize(params, "innerLoops")? {
+        stl_params.inner_loops(v);
+    }
+    if let Some(v) = get_usize(params, "outerLoops")? {
+        stl_params.outer_loops(v);
+    }
+    if let Some(v) = get_bool(params, "robust")? {
+        stl_params.robust(v);
+    }
+    if let Some(v) = get_bool(params, "checkFinite")? {
+        stl_params.check_finite(v);
+    }
+    Ok(stl_params)
+}
+
+fn mstl_params(params: &JsValue) -> Result<MstlParams, JsError> {
+    let mut mstl_params = MstlParams::new();
+    if let Some(v) = get_usize(params, "iterations")? {
+        mstl_params.iterations(v);
+    }
+    if let Some(v) = get_f32(params, "lambda")? {
+        mstl_params.lambda(v);
+    }
+    if let Some(v) = get_bool(params, "checkFinite")? {
+        mstl_params.check_finite(v);
+    }
+    if let Some(v) = get(params, "seasonalLengths")? {
+        if !Array::is_array(&v) {
+            return Err(JsError::new("seasonalLengths must be an array"));
+        }
+        let lengths = Array::from(&v)
+            .iter()
+            .map(|v| to_usize("seasonalLengths", &v))
+            .collect::<Result<Vec<usize>, JsError>>()?;
+        mstl_params.seasonal_lengths(&lengths);
+    }
+    if let Some(v) = get(params, "stlParams")? {
+        mstl_params.stl_params(stl_params(&v)?);
+    }
+    Ok(mstl_params)
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+    use super::*;
+    use crate::stl::test_helpers::*;
+    use js_sys::{Object, Reflect};
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    fn generate_series() -> Float64Array {
+        Float64Array::from(
+            &[
+                5.0, 9.0, 2.0, 9.0, 0.0, 6.0, 3.0, 8.0, 5.0, 8.0, 7.0, 8.0, 8.0, 0.0, 2.0, 5.0,
+                0.0, 5.0, 6.0, 7.0, 3.0, 6.0, 1.0, 4.0, 4.0, 4.0, 3.0, 7.0, 5.0, 8.0,
+            ][..],
+        )
+    }
+
+    fn object(entries: &[(&str, JsValue)]) -> JsValue {
+        let obj = Object::new();
+        for (k, v) in entries {
+            Reflect::set(&obj, &JsValue::from_str(k), v).unwrap();
+        }
+        obj.into()
+    }
+
+    #[wasm_bindgen_test]
+    fn test_stl() {
+        let result = WasmStl::fit(&generate_series(), 7, JsValue::UNDEFINED).unwrap();
+        assert_elements_in_delta(
+            &[0.36926576, 0.75655484, -1.3324139, 1.9553658, -0.6044802],
+            &result.seasonal().to_vec()[..5],
+        );
+        assert_in_delta(0.284111676315015, result.seasonal_strength());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_stl_params() {
+        let params = object(&[("robust", JsValue::TRUE)]);
+        let result = WasmStl::fit(&generate_series(), 7, params).unwrap();
+        assert_elements_in_delta(
+            &[0.99374926, 0.8129377, 0.9385952, 0.9458036, 0.29742217],
+            &result.weights().to_vec()[..5],
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn test_stl_bad_params() {
+        let params = object(&[("seasonalLength", JsValue::from_str("7"))]);
+        assert!(WasmStl::fit(&generate_series(), 7, params).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_mstl() {
+        let result = WasmMstl::fit(&generate_series(), vec![6, 10], JsValue::NULL).unwrap();
+        let seasonal = result.seasonal();
+        assert_eq!(2, seasonal.length());
+        assert_elements_in_delta(
+            &[1.4130436, 1.6048906, 0.050958008, -1.8706754, -1.7704514],
+            &Float64Array::from(seasonal.get(1)).to_vec()[..5],
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn test_mstl_params() {
+        let stl_params = object(&[("seasonalLength", JsValue::from_f64(7.0))]);
+        let params = object(&[("stlParams", stl_params)]);
+        let result = WasmMstl::fit(&generate_series(), vec![7], params).unwrap();
+        assert_in_delta(0.16384245231864702, result.trend_strength());
+    }
+}